@@ -0,0 +1,25 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+/// Generate `ejs.h` from the C ABI in `src/capi.rs` into `OUT_DIR`
+///
+/// The committed `include/ejs.h` is updated from it explicitly with
+/// `cargo run --example update_header --features capi`.
+#[cfg(feature = "capi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("invalid cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/capi.rs", crate_dir))
+        .generate()
+        .expect("failed to generate C header")
+        .write_to_file(format!("{}/ejs.h", out_dir));
+}
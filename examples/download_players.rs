@@ -1,10 +1,13 @@
 //! Download YouTube player files for testing
 //!
 //! Usage: cargo run --example download_players
+//!
+//! Downloads the players of the manifest named by `EJS_CORPUS`, or of
+//! `tests/corpus.json`.
 
-use ytdlp_ejs::test_data::{ALL_VARIANTS, TEST_CASES, get_cache_path, get_player_paths};
 use std::fs;
 use std::path::Path;
+use ytdlp_ejs::test_data::{Corpus, get_cache_path, get_player_paths};
 
 fn main() {
     let player_paths = get_player_paths();
@@ -12,11 +15,10 @@ fn main() {
     let mut skipped = 0;
     let mut failed = 0;
 
-    for test_case in TEST_CASES {
-        let variants = test_case.variants.unwrap_or(ALL_VARIANTS);
-
-        for variant in variants {
-            let cache_path = get_cache_path(test_case.player, variant);
+    let corpus = Corpus::load().expect("Failed to load corpus");
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let cache_path = get_cache_path(&player.player, variant);
             let path = Path::new(&cache_path);
 
             if path.exists() {
@@ -42,7 +44,7 @@ fn main() {
 
             let url = format!(
                 "https://www.youtube.com/s/player/{}/{}",
-                test_case.player, player_path
+                player.player, player_path
             );
 
             println!("Downloading: {}", url);
@@ -0,0 +1,65 @@
+//! Write seed corpora for the cargo-fuzz targets in `fuzz/`
+//!
+//! Usage: cargo run --example fuzz_seeds
+//!
+//! Seeds come from every synthetic player and from the downloaded players
+//! of the manifest named by `EJS_CORPUS`, or of `tests/corpus.json`.
+
+#[path = "../tests/common/synthetic.rs"]
+#[allow(dead_code)]
+mod synthetic;
+use std::fs;
+use std::path::Path;
+use synthetic::SyntheticSpec;
+use ytdlp_ejs::test_data::{Corpus, get_cache_path};
+use ytdlp_ejs::{JsChallengeInput, JsChallengeRequest, JsChallengeType};
+
+const CORPUS_DIR: &str = "fuzz/corpus";
+
+fn main() {
+    let mut players = Vec::new();
+    for spec in SyntheticSpec::all() {
+        players.push((spec.name(), spec.generate().source));
+    }
+    let corpus = Corpus::load().expect("Failed to load corpus");
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let cache_path = get_cache_path(&player.player, variant);
+            if let Ok(source) = fs::read_to_string(&cache_path) {
+                players.push((format!("{}-{}", player.player, variant), source));
+            }
+        }
+    }
+
+    for (name, source) in &players {
+        write_seed("preprocess", name, source);
+        write_seed("solver_templates", name, source);
+        write_seed("extract_jsc_input", name, &jsc_script(source));
+    }
+    println!("Wrote {} seeds per target to {}", players.len(), CORPUS_DIR);
+}
+
+/// The tail of the script yt-dlp hands to `ejs --script`
+fn jsc_script(player: &str) -> String {
+    let input = JsChallengeInput::Player {
+        protocol_version: None,
+        player: player.to_string(),
+        requests: vec![JsChallengeRequest {
+            challenge_type: JsChallengeType::N,
+            challenges: vec!["ZdZIqFPQK-Ty8wId".into()],
+        }],
+        output_preprocessed: false,
+    };
+    let json = serde_json::to_string(&input).expect("Failed to serialize input");
+    format!(
+        "Object.assign(globalThis, lib);\nconsole.log(JSON.stringify(jsc({})));\n",
+        json
+    )
+}
+
+fn write_seed(target: &str, name: &str, data: &str) {
+    let dir = Path::new(CORPUS_DIR).join(target);
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::write(dir.join(name), data)) {
+        eprintln!("Failed to write {} seed {}: {}", target, name, e);
+    }
+}
@@ -2,12 +2,15 @@
 //!
 //! Usage: cargo run --example generate_csv > cases.csv
 //!        cargo run --example generate_csv -- --output cases.csv
+//!
+//! The corpus is read from the manifest named by `EJS_CORPUS`, or from
+//! `tests/corpus.json`.
 
-use ytdlp_ejs::test_data::{ALL_VARIANTS, TEST_CASES, get_cache_path};
 use std::env;
 use std::fs::File;
 use std::io::{self, Write};
 use std::path::Path;
+use ytdlp_ejs::test_data::{Corpus, get_cache_path};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -30,11 +33,10 @@ fn main() {
     let mut count = 0;
     let mut missing = 0;
 
-    for test_case in TEST_CASES {
-        let variants = test_case.variants.unwrap_or(ALL_VARIANTS);
-
-        for variant in variants {
-            let cache_path = get_cache_path(test_case.player, variant);
+    let corpus = Corpus::load().expect("Failed to load corpus");
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let cache_path = get_cache_path(&player.player, variant);
             let path = Path::new(&cache_path);
 
             // Check if player file exists
@@ -43,20 +45,19 @@ fn main() {
                 continue;
             }
 
-            let filename = format!("{}-{}", test_case.player, variant);
-
-            // Export n tests (space-separated)
-            for step in test_case.n {
-                writeln!(output, "{} n {} {}", filename, step.input, step.expected)
-                    .expect("Failed to write");
-                count += 1;
-            }
+            let filename = format!("{}-{}", player.player, variant);
 
-            // Export sig tests (space-separated)
-            for step in test_case.sig {
-                writeln!(output, "{} sig {} {}", filename, step.input, step.expected)
+            // Export n and sig tests (space-separated)
+            for (test_type, steps) in player.steps() {
+                for step in steps {
+                    writeln!(
+                        output,
+                        "{} {} {} {}",
+                        filename, test_type, step.input, step.expected
+                    )
                     .expect("Failed to write");
-                count += 1;
+                    count += 1;
+                }
             }
         }
     }
@@ -0,0 +1,18 @@
+//! Update the committed `include/ejs.h` from the header the build script
+//! generates for the C ABI in `src/capi.rs`
+//!
+//! Usage: cargo run --example update_header --features capi
+
+use std::fs;
+
+const HEADER_PATH: &str = "include/ejs.h";
+
+fn main() {
+    let current = fs::read_to_string(HEADER_PATH).unwrap_or_default();
+    if current == ytdlp_ejs::capi::HEADER {
+        println!("{} is up to date", HEADER_PATH);
+        return;
+    }
+    fs::write(HEADER_PATH, ytdlp_ejs::capi::HEADER).expect("Failed to write header");
+    println!("Updated {}", HEADER_PATH);
+}
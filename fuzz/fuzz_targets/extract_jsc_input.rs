@@ -0,0 +1,13 @@
+//! `extract_jsc_input` scans raw bytes for the jsc() argument; it must
+//! never panic, in particular on multi-byte characters
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ytdlp_ejs::protocol::extract_jsc_input;
+
+fuzz_target!(|script: &str| {
+    let _ = extract_jsc_input(script);
+    // Most inputs lack the marker; also reach the brace matcher directly
+    let _ = extract_jsc_input(&format!("console.log(JSON.stringify(jsc({}", script));
+});
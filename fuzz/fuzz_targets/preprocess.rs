@@ -0,0 +1,9 @@
+//! `preprocess_player` on arbitrary player code must return, never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    let _ = ytdlp_ejs::preprocess_player(code);
+});
@@ -0,0 +1,10 @@
+//! Solver names found in arbitrary code are pasted into the JS templates in
+//! `extract_shared`; generation must fail cleanly, never panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    let _ = ytdlp_ejs::builtin::preprocessor::generate_solver_code(code);
+});
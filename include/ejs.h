@@ -0,0 +1,83 @@
+#ifndef EJS_H
+#define EJS_H
+
+/* Generated by cbindgen from src/capi.rs. Do not edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// A preprocessed player with a live runtime, for repeated requests
+//
+// In-process engines are not thread-safe: use a solver only on the thread
+// that created it.
+typedef struct EjsSolver EjsSolver;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Preprocess `player_len` bytes of player JavaScript at `player` and start
+// the configured runtime with the result
+//
+// `config_json` is a JSON `SolverConfig`, or `NULL` for the defaults.
+// Returns `NULL` on failure. Release with [`ejs_solver_free`].
+//
+// # Safety
+//
+// `player` must point to `player_len` readable bytes and `config_json`
+// must be `NULL` or a NUL-terminated string.
+EjsSolver *ejs_solver_new(const uint8_t *player, size_t player_len, const char *config_json);
+
+// Solve a JSON array of requests (`[{"type": "n", "challenges": [...]}]`)
+// against the solver's player, reusing its runtime
+//
+// Returns a JSON `JsChallengeOutput`; per-challenge failures are reported
+// inside it. Returns `NULL` only if the arguments are invalid.
+//
+// # Safety
+//
+// `solver` must come from [`ejs_solver_new`] on the calling thread and
+// `requests_json` must be a NUL-terminated string.
+char *ejs_solver_solve(const EjsSolver *solver, const char *requests_json);
+
+// Process a complete JSON `JsChallengeInput`, as read by the `ejs` CLI
+//
+// `config_json` is a JSON `SolverConfig`, or `NULL` for the defaults.
+// Returns a JSON `JsChallengeOutput`, or `NULL` if the arguments are
+// invalid.
+//
+// # Safety
+//
+// `input_json` must be a NUL-terminated string and `config_json` must be
+// `NULL` or a NUL-terminated string.
+char *ejs_process_input(const char *input_json, const char *config_json);
+
+// Release a solver; `NULL` is ignored
+//
+// # Safety
+//
+// `solver` must come from [`ejs_solver_new`] and not be used afterwards.
+void ejs_solver_free(EjsSolver *solver);
+
+// Release a string returned by ejs; `NULL` is ignored
+//
+// # Safety
+//
+// `s` must come from ejs and not be used afterwards.
+void ejs_string_free(char *s);
+
+// Message of the last failure on this thread, or `NULL`
+//
+// The string is owned by ejs and valid until the next ejs call on the
+// same thread. Release builds abort on panic, so a panic inside ejs ends
+// the process instead of being reported here.
+const char *ejs_last_error(void);
+
+// ejs version as a static NUL-terminated string
+const char *ejs_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* EJS_H */
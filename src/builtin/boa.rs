@@ -1,6 +1,7 @@
 //! Boa JS Challenge Provider
 
 use crate::provider::JsChallengeError;
+use crate::runtime::{JsRuntime, RuntimeCapabilities};
 use crate::trace::{debug, info};
 use boa_engine::object::ObjectInitializer;
 use boa_engine::property::Attribute;
@@ -24,7 +25,7 @@ impl BoaJCP {
         debug!(code_len = code.len(), "Evaluating preprocessed code in Boa");
         context
             .eval(Source::from_bytes(code))
-            .map_err(|e| JsChallengeError::Runtime(format!("Failed to execute: {}", e)))?;
+            .map_err(|e| JsChallengeError::JsException(format!("Failed to execute: {}", e)))?;
 
         info!("Boa code evaluation complete");
         Ok(Self { context })
@@ -51,7 +52,7 @@ impl BoaJCP {
             .context
             .eval(Source::from_bytes(&call_code))
             .map_err(|e| {
-                JsChallengeError::Runtime(format!("Failed to call {}: {}", func_name, e))
+                JsChallengeError::JsException(format!("Failed to call {}: {}", func_name, e))
             })?;
 
         result
@@ -64,3 +65,20 @@ impl BoaJCP {
             .map_err(|e| JsChallengeError::Runtime(format!("Failed to convert result: {}", e)))
     }
 }
+
+impl JsRuntime for BoaJCP {
+    fn new(code: &str) -> Result<Self, JsChallengeError> {
+        BoaJCP::new(code)
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        self.call_solver(func_name, challenge)
+    }
+
+    fn capabilities(&self) -> RuntimeCapabilities {
+        RuntimeCapabilities {
+            in_process: true,
+            persistent: true,
+        }
+    }
+}
@@ -1,55 +1,99 @@
 //! Bun JS Challenge Provider
 
+use crate::builtin::{ExternalOptions, run_external};
+use crate::config::SolverConfig;
 use crate::provider::JsChallengeError;
+use crate::runtime::JsRuntime;
 use crate::trace::{debug, error};
-use std::io::Write;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
 /// Bun-based JavaScript Challenge Provider
 pub struct BunJCP {
     code: String,
+    options: ExternalOptions,
 }
 
 impl BunJCP {
     pub fn new(code: &str) -> Self {
+        Self::with_config(code, &SolverConfig::default())
+    }
+
+    pub fn with_config(code: &str, config: &SolverConfig) -> Self {
         debug!(code_len = code.len(), "Creating Bun provider");
         Self {
             code: code.to_string(),
+            options: config.into(),
         }
     }
 
     pub fn solve(&self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
         debug!(%func_name, %challenge, script_len = self.code.len(), "Calling solver via Bun");
+        let script = self.script(func_name, challenge);
+        let output = run_external("bun", self.command(), &script, self.options.timeout)?;
+        Self::finish(output)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn solve_async(
+        &self,
+        func_name: &str,
+        challenge: &str,
+    ) -> Result<String, JsChallengeError> {
+        debug!(%func_name, %challenge, script_len = self.code.len(), "Calling solver via Bun (async)");
+        let script = self.script(func_name, challenge);
+        let output = crate::builtin::run_external_async(
+            "bun",
+            self.command(),
+            &script,
+            self.options.timeout,
+        )
+        .await?;
+        Self::finish(output)
+    }
+
+    fn script(&self, func_name: &str, challenge: &str) -> String {
         let escaped = challenge.replace('\\', "\\\\").replace('"', "\\\"");
-        let script = format!(
+        format!(
             "const _result = {{}};\n{}\nconsole.log(_result.{}(\"{}\"));",
             self.code, func_name, escaped
-        );
+        )
+    }
 
-        let mut child = Command::new("bun")
-            .args(["run", "-"])
+    fn command(&self) -> Command {
+        // Bun has no heap limit flag, so `memory_limit` is not applied
+        let mut command = Command::new("bun");
+        command
+            .arg("run")
+            .args(&self.options.args)
+            .arg("-")
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(script.as_bytes())?;
-        }
-
-        let output = child.wait_with_output()?;
+            .stderr(Stdio::piped());
+        command
+    }
 
+    fn finish(output: Output) -> Result<String, JsChallengeError> {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             error!(%stderr, exit_code = ?output.status.code(), "Bun execution failed");
-            return Err(JsChallengeError::Runtime(format!(
+            return Err(JsChallengeError::JsException(format!(
                 "Bun execution failed: {}",
                 stderr.trim()
             )));
         }
 
         let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        debug!(%func_name, result_len = result.len(), result, exit_code = ?output.status.code(), "Bun solver returned");
+        debug!(result_len = result.len(), result, exit_code = ?output.status.code(), "Bun solver returned");
         Ok(result)
     }
 }
+
+impl JsRuntime for BunJCP {
+    fn new(code: &str) -> Result<Self, JsChallengeError> {
+        Ok(BunJCP::new(code))
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        BunJCP::solve(self, func_name, challenge)
+    }
+}
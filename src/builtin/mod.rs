@@ -21,10 +21,174 @@ pub mod node;
 #[cfg(feature = "external")]
 pub mod bun;
 
+use crate::runtime::{JsRuntime, RuntimeCapabilities};
 use crate::{JsChallengeType, provider::JsChallengeError};
 use std::collections::HashMap;
 
-/// Enum-based provider for static dispatch (no trait objects)
+#[cfg(feature = "external")]
+use crate::config::SolverConfig;
+#[cfg(feature = "external")]
+use std::io::{Read, Write};
+#[cfg(feature = "external")]
+use std::process::{Command, Output, Stdio};
+#[cfg(feature = "external")]
+use std::time::{Duration, Instant};
+
+// ── External process helpers ────────────────────────────────────────────────
+
+/// Map a failure to start an external runtime to a typed error
+#[cfg(feature = "external")]
+pub(crate) fn spawn_error(program: &str, e: std::io::Error) -> JsChallengeError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        JsChallengeError::RuntimeUnavailable {
+            runtime: program.to_string(),
+            message: e.to_string(),
+        }
+    } else {
+        JsChallengeError::Io(e)
+    }
+}
+
+/// Check that `program --version` runs successfully
+#[cfg(feature = "external")]
+pub(crate) fn probe(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Settings shared by the external runtime providers
+#[cfg(feature = "external")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExternalOptions {
+    pub timeout: Option<Duration>,
+    /// Heap limit in MB, for V8's `--max-old-space-size`
+    pub max_old_space_mb: Option<usize>,
+    pub args: Vec<String>,
+}
+
+#[cfg(feature = "external")]
+impl From<&SolverConfig> for ExternalOptions {
+    fn from(config: &SolverConfig) -> Self {
+        Self {
+            timeout: config.timeout(),
+            max_old_space_mb: config.memory_limit.map(|bytes| bytes.div_ceil(1024 * 1024)),
+            args: config.runtime_args.clone(),
+        }
+    }
+}
+
+/// Run `command` with `script` on stdin, killing it once `timeout` expires
+#[cfg(feature = "external")]
+pub(crate) fn run_external(
+    program: &str,
+    mut command: Command,
+    script: &str,
+    timeout: Option<Duration>,
+) -> Result<Output, JsChallengeError> {
+    let mut child = command.spawn().map_err(|e| spawn_error(program, e))?;
+
+    // Feed stdin and drain the pipes on helper threads, so a child that
+    // stops reading or fills a pipe cannot block us past the deadline
+    let stdin = child.stdin.take().map(|pipe| feed(pipe, script));
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let status = match timeout {
+        None => child.wait()?,
+        Some(timeout) => {
+            let deadline = Instant::now() + timeout;
+            loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+                if Instant::now() >= deadline {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(JsChallengeError::Timeout(timeout));
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        }
+    };
+
+    // A child that exits without reading its whole script closes the pipe;
+    // its status and stderr explain why, so the broken pipe is not the error
+    if let Some(Ok(Err(e))) = stdin.map(|h| h.join())
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        return Err(e.into());
+    }
+
+    let collect = |handle: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        handle.and_then(|h| h.join().ok()).unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+#[cfg(feature = "external")]
+fn feed(
+    mut pipe: impl Write + Send + 'static,
+    script: &str,
+) -> std::thread::JoinHandle<std::io::Result<()>> {
+    let script = script.as_bytes().to_vec();
+    std::thread::spawn(move || pipe.write_all(&script))
+}
+
+#[cfg(feature = "external")]
+fn drain(mut pipe: impl Read + Send + 'static) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Async variant of [`run_external`]; the child is killed when the
+/// timeout fires or the future is dropped
+#[cfg(all(feature = "external", feature = "tokio"))]
+pub(crate) async fn run_external_async(
+    program: &str,
+    command: Command,
+    script: &str,
+    timeout: Option<Duration>,
+) -> Result<Output, JsChallengeError> {
+    use tokio::io::AsyncWriteExt;
+
+    let run = async {
+        let mut child = tokio::process::Command::from(command)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| spawn_error(program, e))?;
+
+        if let Some(mut stdin) = child.stdin.take()
+            && let Err(e) = stdin.write_all(script.as_bytes()).await
+            && e.kind() != std::io::ErrorKind::BrokenPipe
+        {
+            return Err(e.into());
+        }
+
+        Ok::<_, JsChallengeError>(child.wait_with_output().await?)
+    };
+
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, run)
+            .await
+            .map_err(|_| JsChallengeError::Timeout(timeout))?,
+        None => run.await,
+    }
+}
+
+/// Enum-based provider: built-in engines use static dispatch, only custom
+/// runtimes go through a trait object
 pub enum JsRuntimeProvider {
     #[cfg(feature = "qjs")]
     QuickJS(quickjs::QuickJSJCP),
@@ -36,52 +200,62 @@ pub enum JsRuntimeProvider {
     Node(node::NodeJCP),
     #[cfg(feature = "external")]
     Bun(bun::BunJCP),
+    Custom(Box<dyn JsRuntime>),
 }
 
 impl JsRuntimeProvider {
-    pub fn solve_n(&mut self, challenge: &str) -> Result<String, JsChallengeError> {
+    pub fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
         match self {
             #[cfg(feature = "qjs")]
-            Self::QuickJS(p) => p.solve_n(challenge),
+            Self::QuickJS(p) => p.solve(func_name, challenge),
             #[cfg(feature = "boa")]
-            Self::Boa(p) => p.solve_n(challenge),
+            Self::Boa(p) => p.solve(func_name, challenge),
             #[cfg(feature = "external")]
-            Self::Deno(p) => p.solve("n", challenge),
+            Self::Deno(p) => p.solve(func_name, challenge),
             #[cfg(feature = "external")]
-            Self::Node(p) => p.solve("n", challenge),
+            Self::Node(p) => p.solve(func_name, challenge),
             #[cfg(feature = "external")]
-            Self::Bun(p) => p.solve("n", challenge),
+            Self::Bun(p) => p.solve(func_name, challenge),
+            Self::Custom(p) => p.solve(func_name, challenge),
         }
     }
 
-    pub fn solve_sig(&mut self, challenge: &str) -> Result<String, JsChallengeError> {
+    pub fn capabilities(&self) -> RuntimeCapabilities {
         match self {
             #[cfg(feature = "qjs")]
-            Self::QuickJS(p) => p.solve_sig(challenge),
+            Self::QuickJS(p) => p.capabilities(),
             #[cfg(feature = "boa")]
-            Self::Boa(p) => p.solve_sig(challenge),
+            Self::Boa(p) => p.capabilities(),
             #[cfg(feature = "external")]
-            Self::Deno(p) => p.solve("sig", challenge),
+            Self::Deno(p) => p.capabilities(),
             #[cfg(feature = "external")]
-            Self::Node(p) => p.solve("sig", challenge),
+            Self::Node(p) => p.capabilities(),
             #[cfg(feature = "external")]
-            Self::Bun(p) => p.solve("sig", challenge),
+            Self::Bun(p) => p.capabilities(),
+            Self::Custom(p) => p.capabilities(),
         }
     }
 
+    pub fn solve_n(&mut self, challenge: &str) -> Result<String, JsChallengeError> {
+        self.solve("n", challenge)
+    }
+
+    pub fn solve_sig(&mut self, challenge: &str) -> Result<String, JsChallengeError> {
+        self.solve("sig", challenge)
+    }
+
+    /// Solve each challenge independently; one failure does not discard
+    /// the results of the others
     pub fn solve_challenges(
         &mut self,
         req_type: &JsChallengeType,
         challenges: &[String],
-    ) -> Result<HashMap<String, String>, JsChallengeError> {
+    ) -> HashMap<String, Result<String, JsChallengeError>> {
         let mut results = HashMap::with_capacity(challenges.len());
         for challenge in challenges {
-            let result = match req_type {
-                JsChallengeType::N => self.solve_n(challenge)?,
-                JsChallengeType::Sig => self.solve_sig(challenge)?,
-            };
+            let result = self.solve(req_type.as_str(), challenge);
             results.insert(challenge.clone(), result);
         }
-        Ok(results)
+        results
     }
 }
@@ -1,55 +1,100 @@
 //! Node.js JS Challenge Provider
 
+use crate::builtin::{ExternalOptions, run_external};
+use crate::config::SolverConfig;
 use crate::provider::JsChallengeError;
+use crate::runtime::JsRuntime;
 use crate::trace::{debug, error};
-use std::io::Write;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
 /// Node.js-based JavaScript Challenge Provider
 pub struct NodeJCP {
     code: String,
+    options: ExternalOptions,
 }
 
 impl NodeJCP {
     pub fn new(code: &str) -> Self {
+        Self::with_config(code, &SolverConfig::default())
+    }
+
+    pub fn with_config(code: &str, config: &SolverConfig) -> Self {
         debug!(code_len = code.len(), "Creating Node.js provider");
         Self {
             code: code.to_string(),
+            options: config.into(),
         }
     }
 
     pub fn solve(&self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
         debug!(%func_name, %challenge, script_len = self.code.len(), "Calling solver via Node.js");
+        let script = self.script(func_name, challenge);
+        let output = run_external("node", self.command(), &script, self.options.timeout)?;
+        Self::finish(output)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn solve_async(
+        &self,
+        func_name: &str,
+        challenge: &str,
+    ) -> Result<String, JsChallengeError> {
+        debug!(%func_name, %challenge, script_len = self.code.len(), "Calling solver via Node.js (async)");
+        let script = self.script(func_name, challenge);
+        let output = crate::builtin::run_external_async(
+            "node",
+            self.command(),
+            &script,
+            self.options.timeout,
+        )
+        .await?;
+        Self::finish(output)
+    }
+
+    fn script(&self, func_name: &str, challenge: &str) -> String {
         let escaped = challenge.replace('\\', "\\\\").replace('"', "\\\"");
-        let script = format!(
+        format!(
             "const _result = {{}};\n{}\nconsole.log(_result.{}(\"{}\"));",
             self.code, func_name, escaped
-        );
+        )
+    }
 
-        let mut child = Command::new("node")
+    fn command(&self) -> Command {
+        let mut command = Command::new("node");
+        if let Some(mb) = self.options.max_old_space_mb {
+            command.arg(format!("--max-old-space-size={}", mb));
+        }
+        command
+            .args(&self.options.args)
             .arg("-")
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
-
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin.write_all(script.as_bytes())?;
-        }
-
-        let output = child.wait_with_output()?;
+            .stderr(Stdio::piped());
+        command
+    }
 
+    fn finish(output: Output) -> Result<String, JsChallengeError> {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             error!(%stderr, exit_code = ?output.status.code(), "Node.js execution failed");
-            return Err(JsChallengeError::Runtime(format!(
+            return Err(JsChallengeError::JsException(format!(
                 "Node execution failed: {}",
                 stderr.trim()
             )));
         }
 
         let result = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        debug!(%func_name, result_len = result.len(), result, exit_code = ?output.status.code(), "Node.js solver returned");
+        debug!(result_len = result.len(), result, exit_code = ?output.status.code(), "Node.js solver returned");
         Ok(result)
     }
 }
+
+impl JsRuntime for NodeJCP {
+    fn new(code: &str) -> Result<Self, JsChallengeError> {
+        Ok(NodeJCP::new(code))
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        NodeJCP::solve(self, func_name, challenge)
+    }
+}
@@ -8,3 +8,6 @@ pub const SETUP_CODE: &str = include_str!("polyfill/setup.js");
 
 /// Intl polyfill for QuickJS compatibility
 pub const INTL_POLYFILL: &str = include_str!("polyfill/intl.js");
+
+/// Host globals for script mode: console, print, timers and `std`
+pub const SCRIPT_HOST: &str = include_str!("polyfill/script.js");
@@ -0,0 +1,164 @@
+// Host environment for script mode (`ejs --script`, qjs compatibility).
+// Rust provides __ejs_host and reads back __ejs_console_output (stdout),
+// __ejs_timers (driven by the event loop once the job queue is empty) and
+// __ejs_rejections (fed by the runtime's promise rejection tracker).
+(function (host) {
+  var output = [];
+  globalThis.__ejs_console_output = output;
+
+  function format(value) {
+    if (typeof value === "string") {
+      return value;
+    }
+    if (value instanceof Error) {
+      return value.stack ? value.name + ": " + value.message + "\n" + value.stack : String(value);
+    }
+    if (value !== null && typeof value === "object") {
+      try {
+        var json = JSON.stringify(value);
+        if (json !== undefined) {
+          return json;
+        }
+      } catch (e) {}
+    }
+    return String(value);
+  }
+
+  function line(args) {
+    return Array.prototype.map.call(args, format).join(" ") + "\n";
+  }
+
+  // stdout is buffered and returned to Rust. stderr is written directly,
+  // and only when the script asks for it: yt-dlp treats any stderr output
+  // as a failure, so nothing else may go there.
+  function stdout() {
+    output.push(line(arguments));
+  }
+  function stderr() {
+    host.stderr(line(arguments));
+  }
+
+  globalThis.console = {
+    log: stdout,
+    info: stdout,
+    debug: stdout,
+    warn: stderr,
+    error: stderr,
+    trace: stderr,
+  };
+  globalThis.print = stdout;
+
+  var timers = [];
+  var nextId = 1;
+
+  function schedule(callback, delay, args, repeat) {
+    var ms = Math.max(0, Number(delay) || 0);
+    var id = nextId++;
+    timers.push({ id: id, at: Date.now() + ms, interval: repeat ? ms : -1, callback: callback, args: args });
+    return id;
+  }
+  function clear(id) {
+    for (var i = 0; i < timers.length; i++) {
+      if (timers[i].id === id) {
+        timers.splice(i, 1);
+        return;
+      }
+    }
+  }
+
+  globalThis.setTimeout = function (callback, delay) {
+    return schedule(callback, delay, Array.prototype.slice.call(arguments, 2), false);
+  };
+  globalThis.setInterval = function (callback, delay) {
+    return schedule(callback, delay, Array.prototype.slice.call(arguments, 2), true);
+  };
+  globalThis.clearTimeout = clear;
+  globalThis.clearInterval = clear;
+  globalThis.queueMicrotask = function (callback) {
+    Promise.resolve().then(callback);
+  };
+
+  function earliest() {
+    var index = -1;
+    for (var i = 0; i < timers.length; i++) {
+      if (index < 0 || timers[i].at < timers[index].at) {
+        index = i;
+      }
+    }
+    return index;
+  }
+
+  globalThis.__ejs_timers = {
+    // Milliseconds until the next timer is due, or -1 when none is left
+    next: function () {
+      var index = earliest();
+      return index < 0 ? -1 : Math.max(0, timers[index].at - Date.now());
+    },
+    fire: function () {
+      var index = earliest();
+      if (index < 0) {
+        return;
+      }
+      var timer = timers[index];
+      timers.splice(index, 1);
+      if (timer.interval >= 0) {
+        timer.at = Date.now() + timer.interval;
+        timers.push(timer);
+      }
+      if (typeof timer.callback === "function") {
+        timer.callback.apply(globalThis, timer.args);
+      } else {
+        (0, eval)(String(timer.callback));
+      }
+    },
+  };
+
+  // Rejections still without a handler once the event loop is done are
+  // reported on stderr and fail the script, as in qjs
+  var rejections = [];
+  globalThis.__ejs_rejections = {
+    track: function (promise, reason, handled) {
+      if (!handled) {
+        rejections.push({ promise: promise, reason: reason });
+        return;
+      }
+      for (var i = 0; i < rejections.length; i++) {
+        if (rejections[i].promise === promise) {
+          rejections.splice(i, 1);
+          return;
+        }
+      }
+    },
+    // Number of unhandled rejections, after writing them to stderr
+    report: function () {
+      for (var i = 0; i < rejections.length; i++) {
+        host.stderr(line(["Possibly unhandled promise rejection:", rejections[i].reason]));
+      }
+      return rejections.length;
+    },
+  };
+
+  // Subset of the QuickJS std module (`--std`); there is no `os`
+  if (host.std) {
+    var out = {
+      puts: function (s) {
+        output.push(String(s));
+      },
+      flush: function () {},
+    };
+    var err = {
+      puts: function (s) {
+        host.stderr(String(s));
+      },
+      flush: function () {},
+    };
+    globalThis.std = {
+      out: out,
+      err: err,
+      puts: out.puts,
+      getenv: host.getenv,
+      loadFile: host.loadFile,
+    };
+  }
+})(globalThis.__ejs_host);
+delete globalThis.__ejs_host;
@@ -36,3 +36,62 @@ if (typeof globalThis.window === "undefined") {
 if (typeof globalThis.g === "undefined") {
   globalThis.g = {};
 }
+// Deterministic mode. Preprocessing defines `__ejs_deterministic` as
+// `{ seed, now }` ahead of this file when it is enabled, so every runtime
+// sees the same random sequence, clock and timers.
+if (typeof globalThis.__ejs_deterministic === "object") {
+  (function (options) {
+    // mulberry32
+    var state = options.seed >>> 0;
+    Math.random = function random() {
+      state = (state + 0x6d2b79f5) | 0;
+      var t = Math.imul(state ^ (state >>> 15), 1 | state);
+      t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+      return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+    };
+
+    var now = options.now;
+    var NativeDate = Date;
+    function FrozenDate() {
+      if (!new.target) {
+        return new NativeDate(now).toString();
+      }
+      return arguments.length === 0
+        ? new NativeDate(now)
+        : Reflect.construct(NativeDate, arguments);
+    }
+    FrozenDate.prototype = NativeDate.prototype;
+    FrozenDate.UTC = NativeDate.UTC;
+    FrozenDate.parse = NativeDate.parse;
+    FrozenDate.now = function () {
+      return now;
+    };
+    globalThis.Date = FrozenDate;
+
+    var stub = function (name, value) {
+      Object.defineProperty(globalThis, name, {
+        value: value,
+        writable: true,
+        configurable: true,
+      });
+    };
+    stub("performance", {
+      timeOrigin: now,
+      now: function () {
+        return 0;
+      },
+    });
+    // Timers never fire: solvers are synchronous, and engines differ in
+    // whether they have an event loop at all
+    var nextTimer = 1;
+    var setTimer = function () {
+      return nextTimer++;
+    };
+    var clearTimer = function () {};
+    stub("setTimeout", setTimer);
+    stub("setInterval", setTimer);
+    stub("clearTimeout", clearTimer);
+    stub("clearInterval", clearTimer);
+  })(globalThis.__ejs_deterministic);
+  delete globalThis.__ejs_deterministic;
+}
@@ -311,7 +311,7 @@ pub fn parse_script(code: &str) -> Result<Vec<Stmt>, JsChallengeError> {
     let mut parser = Parser::new_from(lexer);
     let module = parser
         .parse_module()
-        .map_err(|e| JsChallengeError::Parse(format!("{:?}", e)))?;
+        .map_err(|e| JsChallengeError::parse(format!("{:?}", e)))?;
 
     let stmts = module
         .body
@@ -343,12 +343,12 @@ pub fn parse_expr(code: &str) -> Result<Box<Expr>, JsChallengeError> {
     let mut parser = Parser::new_from(lexer);
     let module = parser
         .parse_module()
-        .map_err(|e| JsChallengeError::Parse(format!("{:?}", e)))?;
+        .map_err(|e| JsChallengeError::parse(format!("{:?}", e)))?;
 
     if let Some(ModuleItem::Stmt(Stmt::Expr(expr_stmt))) = module.body.first() {
         Ok(expr_stmt.expr.clone())
     } else {
-        Err(JsChallengeError::Parse("Failed to parse expression".into()))
+        Err(JsChallengeError::parse("Failed to parse expression"))
     }
 }
 
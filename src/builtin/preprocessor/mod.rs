@@ -4,21 +4,78 @@
 //! the IIFE wrapper, filters statements, locates n/sig solver functions,
 //! and generates the final preprocessed code with solver assignments.
 
-mod extract_shared;
+pub(crate) mod extract_shared;
 
-use swc_common::{DUMMY_SP, FileName, SourceMap, SyntaxContext, sync::Lrc};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use swc_common::{DUMMY_SP, FileName, SourceMap, Spanned, SyntaxContext, sync::Lrc};
 use swc_ecma_ast::*;
 use swc_ecma_codegen::{Config, Emitter, text_writer::JsWriter};
 use swc_ecma_parser::{Parser, StringInput, Syntax, lexer::Lexer};
 
 use crate::builtin::polyfill::{INTL_POLYFILL, SETUP_CODE};
+use crate::config::{PolyfillProfile, SolverConfig};
 use crate::provider::JsChallengeError;
 use crate::trace::{debug, info, trace_span};
 
 /// Preprocess YouTube player code to extract sig and n functions.
 /// Returns the final executable preprocessed JavaScript code.
 pub fn preprocess_player(data: &str) -> Result<String, JsChallengeError> {
+    preprocess_player_with(data, &SolverConfig::default())
+}
+
+/// Like [`preprocess_player`], honouring the polyfill profile,
+/// deterministic mode and minification settings of `config`
+pub fn preprocess_player_with(
+    data: &str,
+    config: &SolverConfig,
+) -> Result<String, JsChallengeError> {
+    preprocess_player_report(data, config).map(|(code, _)| code)
+}
+
+/// Time spent in each preprocessing stage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PreprocessTimings {
+    /// Lexing and parsing the player
+    pub parse: Duration,
+    /// Unwrapping the IIFE and filtering its statements
+    pub filter: Duration,
+    /// Locating solvers and adding the `_result` assignments
+    pub extract: Duration,
+    /// Adding polyfills and generating the final code
+    pub codegen: Duration,
+}
+
+/// What preprocessing found in a player, besides the generated code
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreprocessReport {
+    pub timings: PreprocessTimings,
+    /// Names of the solver functions found, in source order
+    pub solvers: Vec<String>,
+    /// Candidate count per `_result` property (`n`, `sig` and custom extractors)
+    pub candidates: BTreeMap<String, usize>,
+    /// Custom extractors that returned an error, by name; their `_result`
+    /// entry throws
+    pub extractor_errors: BTreeMap<String, String>,
+}
+
+/// Like [`preprocess_player_with`], also reporting how long each stage took
+pub fn preprocess_player_timed(
+    data: &str,
+    config: &SolverConfig,
+) -> Result<(String, PreprocessTimings), JsChallengeError> {
+    preprocess_player_report(data, config).map(|(code, report)| (code, report.timings))
+}
+
+/// Like [`preprocess_player_with`], also reporting stage timings and the
+/// solvers that were found
+pub fn preprocess_player_report(
+    data: &str,
+    config: &SolverConfig,
+) -> Result<(String, PreprocessReport), JsChallengeError> {
     trace_span!("preprocess_player", input_len = data.len());
+    let mut report = PreprocessReport::default();
+    let mut stage = Instant::now();
 
     #[cfg(feature = "tracing")]
     {
@@ -41,10 +98,22 @@ pub fn preprocess_player(data: &str) -> Result<String, JsChallengeError> {
 
     let mut parser = Parser::new_from(lexer);
     info!("Parsing player JavaScript");
-    let mut module = parser
-        .parse_module()
-        .map_err(|e| JsChallengeError::Parse(format!("{:?}", e)))?;
+    let mut module = parser.parse_module().map_err(|e| {
+        let (line, column) = if e.span().is_dummy() {
+            (None, None)
+        } else {
+            let loc = cm.lookup_char_pos(e.span().lo);
+            (Some(loc.line), Some(loc.col_display))
+        };
+        JsChallengeError::Parse {
+            message: format!("{:?}", e.kind()),
+            line,
+            column,
+        }
+    })?;
     debug!("Player JavaScript parsed successfully");
+    report.timings.parse = stage.elapsed();
+    stage = Instant::now();
 
     // Extract the inner function body from the IIFE wrapper
     let block_stmts = extract_main_block_mut(&mut module)?;
@@ -90,17 +159,38 @@ pub fn preprocess_player(data: &str) -> Result<String, JsChallengeError> {
     }
     *block_stmts = kept;
     debug!(kept_count = block_stmts.len(), "Filtered statements");
+    report.timings.filter = stage.elapsed();
+    stage = Instant::now();
 
     // Extract solvers from block statements
     let mut found_n: Vec<Box<Expr>> = Vec::new();
     let mut found_sig: Vec<Box<Expr>> = Vec::new();
+    let extractors = crate::registry::extractors();
+    let mut found_custom: Vec<Vec<Box<Expr>>> = vec![Vec::new(); extractors.len()];
 
     for stmt in block_stmts.iter() {
         for info in extract_shared::extract_solver_infos(stmt) {
+            report.solvers.push(info.name_expr.clone());
             let solver = extract_shared::generate_solver_expr(&info.name_expr)?;
             found_n.push(extract_shared::generate_n_solver_expr(&solver)?);
             found_sig.push(extract_shared::generate_sig_solver_expr(&solver)?);
         }
+        for (extractor, found) in extractors.iter().zip(&mut found_custom) {
+            if report.extractor_errors.contains_key(extractor.name()) {
+                continue;
+            }
+            // A failing extractor only loses its own type; n and sig stay
+            match extractor.extract(stmt) {
+                Ok(candidates) => found.extend(candidates),
+                Err(e) => {
+                    info!(name = extractor.name(), %e, "Custom extractor failed");
+                    found.clear();
+                    report
+                        .extractor_errors
+                        .insert(extractor.name().to_string(), e.to_string());
+                }
+            }
+        }
     }
 
     info!(
@@ -109,11 +199,13 @@ pub fn preprocess_player(data: &str) -> Result<String, JsChallengeError> {
         "Extracted solver functions"
     );
 
+    report.candidates.insert("n".into(), found_n.len());
+    report.candidates.insert("sig".into(), found_sig.len());
     if found_n.is_empty() {
-        return Err(JsChallengeError::Preprocess("found 0 n functions".into()));
+        return Err(JsChallengeError::NoSolver("n".into()));
     }
     if found_sig.is_empty() {
-        return Err(JsChallengeError::Preprocess("found 0 sig functions".into()));
+        return Err(JsChallengeError::NoSolver("sig".into()));
     }
 
     // Add _result.n / _result.sig assignments with multiTry wrappers
@@ -128,22 +220,79 @@ pub fn preprocess_player(data: &str) -> Result<String, JsChallengeError> {
         "sig",
         extract_shared::generate_multi_try_expr(&found_sig)?,
     ));
+    for (extractor, found) in extractors.iter().zip(&found_custom) {
+        report
+            .candidates
+            .insert(extractor.name().to_string(), found.len());
+        debug!(
+            name = extractor.name(),
+            candidates = found.len(),
+            "Extracted custom solver"
+        );
+        block_stmts.push(make_result_assign(
+            &_result,
+            extractor.name(),
+            extract_shared::generate_multi_try_expr(found)?,
+        ));
+    }
+
+    report.timings.extract = stage.elapsed();
+    stage = Instant::now();
 
     // Prepend polyfills (browser env shims) to the module body
-    debug!("Adding polyfills and generating final code");
-    let mut polyfills: Vec<ModuleItem> = extract_shared::parse_script(INTL_POLYFILL)?
-        .into_iter()
-        .chain(extract_shared::parse_script(SETUP_CODE)?)
-        .map(ModuleItem::Stmt)
-        .collect();
+    debug!(profile = ?config.polyfills, "Adding polyfills and generating final code");
+    let sources: &[&str] = match config.polyfills {
+        PolyfillProfile::Full => &[INTL_POLYFILL, SETUP_CODE],
+        PolyfillProfile::Minimal => &[SETUP_CODE],
+        PolyfillProfile::None => &[],
+    };
+    let mut polyfills = Vec::new();
+    if let Some(deterministic) = config.deterministic.filter(|_| !sources.is_empty()) {
+        let options = format!(
+            "globalThis.__ejs_deterministic = {{ seed: {}, now: {} }};",
+            deterministic.seed, deterministic.now_ms
+        );
+        polyfills.extend(
+            extract_shared::parse_script(&options)?
+                .into_iter()
+                .map(ModuleItem::Stmt),
+        );
+    }
+    for source in sources {
+        polyfills.extend(
+            extract_shared::parse_script(source)?
+                .into_iter()
+                .map(ModuleItem::Stmt),
+        );
+    }
 
     let original_body = std::mem::take(&mut module.body);
     polyfills.extend(original_body);
     module.body = polyfills;
 
-    let code = generate_code(&cm, &module)?;
+    let code = generate_code(&cm, &module, config.minify)?;
+    report.timings.codegen = stage.elapsed();
     info!(output_len = code.len(), "Preprocessing complete");
-    Ok(code)
+    Ok((code, report))
+}
+
+/// Generate the n/sig solver wrappers for every solver candidate found in
+/// `code`, as preprocessing does, and return their code
+///
+/// Exposed for the `solver_templates` fuzz target.
+#[cfg(feature = "fuzzing")]
+pub fn generate_solver_code(code: &str) -> Result<Vec<String>, JsChallengeError> {
+    let mut generated = Vec::new();
+    for stmt in extract_shared::parse_script(code)? {
+        for info in extract_shared::extract_solver_infos(&stmt) {
+            let solver = extract_shared::generate_solver_expr(&info.name_expr)?;
+            let n = extract_shared::generate_n_solver_expr(&solver)?;
+            let sig = extract_shared::generate_sig_solver_expr(&solver)?;
+            let multi_try = extract_shared::generate_multi_try_expr(&[n, sig])?;
+            generated.push(extract_shared::expr_to_code_string_via_codegen(&multi_try)?);
+        }
+    }
+    Ok(generated)
 }
 
 // ── Helpers ──────────────────────────────────────────────────────────────────
@@ -189,7 +338,7 @@ fn extract_main_block_mut(module: &mut Module) -> Result<&mut Vec<Stmt>, JsChall
                     _ => {}
                 }
             }
-            Err(JsChallengeError::Parse(
+            Err(JsChallengeError::UnsupportedPlayer(
                 "unexpected structure (single item)".into(),
             ))
         }
@@ -255,11 +404,11 @@ fn extract_main_block_mut(module: &mut Module) -> Result<&mut Vec<Stmt>, JsChall
                     _ => {}
                 }
             }
-            Err(JsChallengeError::Parse(
+            Err(JsChallengeError::UnsupportedPlayer(
                 "unexpected structure (two items)".into(),
             ))
         }
-        n => Err(JsChallengeError::Parse(format!(
+        n => Err(JsChallengeError::UnsupportedPlayer(format!(
             "unexpected structure: {} items",
             n
         ))),
@@ -281,12 +430,16 @@ fn transform_this_or_self_mut(stmt: &mut Stmt) {
     }
 }
 
-fn generate_code(cm: &Lrc<SourceMap>, module: &Module) -> Result<String, JsChallengeError> {
+fn generate_code(
+    cm: &Lrc<SourceMap>,
+    module: &Module,
+    minify: bool,
+) -> Result<String, JsChallengeError> {
     let mut buf = vec![];
     {
         let writer = JsWriter::new(cm.clone(), "\n", &mut buf, None);
         let mut emitter = Emitter {
-            cfg: Config::default(),
+            cfg: Config::default().with_minify(minify),
             cm: cm.clone(),
             comments: None,
             wr: writer,
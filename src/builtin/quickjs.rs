@@ -1,56 +1,115 @@
 //! QuickJS JS Challenge Provider
 
+use crate::builtin::polyfill::SCRIPT_HOST;
+use crate::config::SolverConfig;
 use crate::provider::JsChallengeError;
+use crate::runtime::{JsRuntime, RuntimeCapabilities};
 use crate::trace::{debug, info};
-use rquickjs::{Context, Function, Object, Runtime};
+use rquickjs::{Context, Ctx, Function, Module, Object, Runtime, Value};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 /// QuickJS-based JavaScript Challenge Provider
 pub struct QuickJSJCP {
     context: Context,
+    timeout: Option<Duration>,
+    /// Checked by the interrupt handler; `None` while idle
+    deadline: Arc<Mutex<Option<Instant>>>,
 }
 
 impl QuickJSJCP {
     pub fn new(code: &str) -> Result<Self, JsChallengeError> {
+        Self::with_config(code, &SolverConfig::default())
+    }
+
+    pub fn with_config(code: &str, config: &SolverConfig) -> Result<Self, JsChallengeError> {
         info!("Creating QuickJS runtime");
         let runtime = Runtime::new()
             .map_err(|e| JsChallengeError::Runtime(format!("Failed to create runtime: {}", e)))?;
-        runtime.set_max_stack_size(16 * 1024 * 1024);
+        runtime.set_max_stack_size(config.stack_size);
+        if let Some(limit) = config.memory_limit {
+            runtime.set_memory_limit(limit);
+        }
+
+        let deadline = Arc::new(Mutex::new(None::<Instant>));
+        if config.timeout_ms.is_some() {
+            let deadline = deadline.clone();
+            runtime.set_interrupt_handler(Some(Box::new(move || {
+                deadline
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .is_some_and(|d| Instant::now() >= d)
+            })));
+        }
+
         let context = Context::full(&runtime)
             .map_err(|e| JsChallengeError::Runtime(format!("Failed to create context: {}", e)))?;
 
-        context.with(|ctx| {
-            let globals = ctx.globals();
-            let result_obj = Object::new(ctx.clone()).map_err(|e| {
-                JsChallengeError::Runtime(format!("Failed to create object: {}", e))
-            })?;
-            globals
-                .set("_result", result_obj)
-                .map_err(|e| JsChallengeError::Runtime(format!("Failed to set _result: {}", e)))?;
-
-            debug!(
-                code_len = code.len(),
-                "Evaluating preprocessed code in QuickJS"
-            );
-            ctx.eval::<(), _>(code).map_err(|e| {
-                let err_msg = match &e {
-                    rquickjs::Error::Exception => {
-                        let exc = ctx.catch();
-                        if exc.is_null() || exc.is_undefined() {
-                            "Exception (no details)".to_string()
-                        } else {
-                            format!("Exception: {:?}", exc)
+        let jcp = Self {
+            context,
+            timeout: config.timeout(),
+            deadline,
+        };
+
+        jcp.timed(|| {
+            jcp.context.with(|ctx| {
+                let globals = ctx.globals();
+                let result_obj = Object::new(ctx.clone()).map_err(|e| {
+                    JsChallengeError::Runtime(format!("Failed to create object: {}", e))
+                })?;
+                globals.set("_result", result_obj).map_err(|e| {
+                    JsChallengeError::Runtime(format!("Failed to set _result: {}", e))
+                })?;
+
+                debug!(
+                    code_len = code.len(),
+                    "Evaluating preprocessed code in QuickJS"
+                );
+                ctx.eval::<(), _>(code).map_err(|e| {
+                    let err_msg = match &e {
+                        rquickjs::Error::Exception => {
+                            let exc = ctx.catch();
+                            if exc.is_null() || exc.is_undefined() {
+                                "Exception (no details)".to_string()
+                            } else {
+                                format!("Exception: {:?}", exc)
+                            }
                         }
-                    }
-                    _ => format!("{:?}", e),
-                };
-                JsChallengeError::Runtime(format!("Failed to execute: {}", err_msg))
-            })?;
+                        _ => format!("{:?}", e),
+                    };
+                    JsChallengeError::JsException(format!("Failed to execute: {}", err_msg))
+                })?;
 
-            info!("QuickJS code evaluation complete");
-            Ok::<(), JsChallengeError>(())
+                info!("QuickJS code evaluation complete");
+                Ok::<(), JsChallengeError>(())
+            })
         })?;
 
-        Ok(Self { context })
+        Ok(jcp)
+    }
+
+    /// Run `f` under the configured timeout, reporting an interrupted
+    /// evaluation as [`JsChallengeError::Timeout`]
+    fn timed<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, JsChallengeError>,
+    ) -> Result<T, JsChallengeError> {
+        let Some(timeout) = self.timeout else {
+            return f();
+        };
+        let set_deadline = |deadline| {
+            *self.deadline.lock().unwrap_or_else(PoisonError::into_inner) = deadline;
+        };
+
+        let start = Instant::now();
+        set_deadline(Some(start + timeout));
+        let result = f();
+        set_deadline(None);
+
+        match result {
+            Err(_) if start.elapsed() >= timeout => Err(JsChallengeError::Timeout(timeout)),
+            result => result,
+        }
     }
 
     pub fn solve_n(&self, challenge: &str) -> Result<String, JsChallengeError> {
@@ -62,6 +121,14 @@ impl QuickJSJCP {
     }
 
     fn call_solver(&self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        self.timed(|| self.call_solver_untimed(func_name, challenge))
+    }
+
+    fn call_solver_untimed(
+        &self,
+        func_name: &str,
+        challenge: &str,
+    ) -> Result<String, JsChallengeError> {
         self.context.with(|ctx| {
             debug!(%func_name, %challenge, "Calling solver");
             let globals = ctx.globals();
@@ -84,7 +151,7 @@ impl QuickJSJCP {
                     }
                     _ => format!("{:?}", e),
                 };
-                JsChallengeError::Runtime(format!("Failed to call {}: {}", func_name, err_msg))
+                JsChallengeError::JsException(format!("Failed to call {}: {}", func_name, err_msg))
             })?;
             debug!(%func_name, %challenge, result_len = result.len(), result, "Solver returned");
             Ok(result)
@@ -92,57 +159,193 @@ impl QuickJSJCP {
     }
 }
 
+impl JsRuntime for QuickJSJCP {
+    fn new(code: &str) -> Result<Self, JsChallengeError> {
+        QuickJSJCP::new(code)
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        self.call_solver(func_name, challenge)
+    }
+
+    fn capabilities(&self) -> RuntimeCapabilities {
+        RuntimeCapabilities {
+            in_process: true,
+            persistent: true,
+        }
+    }
+}
+
 // ── Script mode (bare JS interpreter for yt-dlp integration) ────────────────
 //
 // When invoked as `ejs --script <file>`, we act as a QuickJS-compatible JS
 // runtime. yt-dlp passes the full solver program (lib + core + jsc() call)
 // via a temp file, and expects the JSON result from console.log() on stdout.
+// The other common `qjs` flags map onto [`ScriptOptions`].
+
+/// Options for [`run_script_with`], mirroring the `qjs` command line
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOptions {
+    /// Script name used for module resolution and error messages
+    pub filename: Option<String>,
+    /// Evaluate as an ES module (`qjs -m`)
+    pub module: bool,
+    /// Provide a `std` global (`qjs --std`); only `puts`, `out`, `err`,
+    /// `getenv` and `loadFile` are implemented, and there is no `os`
+    pub std: bool,
+    /// Exposed to the script as `scriptArgs`
+    pub args: Vec<String>,
+    /// Heap limit in bytes
+    pub memory_limit: Option<usize>,
+    /// JS stack limit in bytes; defaults to [`DEFAULT_STACK_SIZE`](crate::config::DEFAULT_STACK_SIZE)
+    pub stack_size: Option<usize>,
+}
 
 /// Execute arbitrary JavaScript code using embedded QuickJS.
-/// Captures all `console.log()` output and returns it as a String.
-/// Errors during JS evaluation are returned as `JsChallengeError::Runtime`.
+/// Captures all stdout output (`console.log()`, `print()`, ...) and returns
+/// it as a String once pending jobs and timers have run.
+/// Errors during JS evaluation are returned as `JsChallengeError::JsException`.
 pub fn run_script(code: &str) -> Result<String, JsChallengeError> {
-    info!(code_len = code.len(), "Running script via embedded QuickJS");
+    run_script_with(code, &ScriptOptions::default())
+}
+
+/// Like [`run_script`], with `qjs`-style options
+pub fn run_script_with(code: &str, options: &ScriptOptions) -> Result<String, JsChallengeError> {
+    info!(
+        code_len = code.len(),
+        module = options.module,
+        "Running script via embedded QuickJS"
+    );
 
     let runtime = Runtime::new()
         .map_err(|e| JsChallengeError::Runtime(format!("Failed to create runtime: {}", e)))?;
     // Default JS stack is only 256KB — far too small for meriyah-based
     // yt-dlp solver scripts. Bump to 16MB to match the Rust thread stack.
-    runtime.set_max_stack_size(16 * 1024 * 1024);
+    runtime.set_max_stack_size(
+        options
+            .stack_size
+            .unwrap_or(crate::config::DEFAULT_STACK_SIZE),
+    );
+    if let Some(limit) = options.memory_limit {
+        runtime.set_memory_limit(limit);
+    }
+    runtime.set_host_promise_rejection_tracker(Some(Box::new(track_rejection)));
     let context = Context::full(&runtime)
         .map_err(|e| JsChallengeError::Runtime(format!("Failed to create context: {}", e)))?;
 
     context.with(|ctx| {
-        // Override console.log to capture output into a JS array.
-        // After execution we read the array back as a Vec<String>.
-        ctx.eval::<(), _>(
-            "var __ejs_console_output = []; \
-             var console = { log: function(m) { __ejs_console_output.push(''+m); } };",
-        )
-        .map_err(|e| {
-            JsChallengeError::Runtime(format!("Failed to setup console capture: {:?}", e))
+        // stdout is captured into a JS array and read back at the end
+        install_host(&ctx, options).map_err(|e| {
+            JsChallengeError::Runtime(format!("Failed to set up script globals: {:?}", e))
         })?;
 
-        ctx.eval::<(), _>(code).map_err(|e| {
-            let err_msg = match &e {
-                rquickjs::Error::Exception => {
-                    let exc = ctx.catch();
-                    if exc.is_null() || exc.is_undefined() {
-                        "Exception (no details)".to_string()
-                    } else {
-                        format!("Exception: {:?}", exc)
-                    }
-                }
-                _ => format!("{:?}", e),
-            };
-            JsChallengeError::Runtime(format!("Script execution error: {}", err_msg))
-        })?;
+        let name = options.filename.as_deref().unwrap_or("<input>");
+        if options.module {
+            let promise =
+                Module::evaluate(ctx.clone(), name, code).map_err(|e| script_error(&ctx, e))?;
+            run_event_loop(&ctx).map_err(|e| script_error(&ctx, e))?;
+            // A top-level await that never settles ends the script, as in qjs
+            if let Some(result) = promise.result::<()>() {
+                result.map_err(|e| script_error(&ctx, e))?;
+            }
+        } else {
+            ctx.eval::<(), _>(code).map_err(|e| script_error(&ctx, e))?;
+            run_event_loop(&ctx).map_err(|e| script_error(&ctx, e))?;
+        }
+        check_rejections(&ctx)?;
 
         let output: Vec<String> = ctx.globals().get("__ejs_console_output").map_err(|e| {
             JsChallengeError::Runtime(format!("Failed to read console output: {:?}", e))
         })?;
 
-        info!(lines = output.len(), "Script completed");
-        Ok(output.join("\n"))
+        info!(chunks = output.len(), "Script completed");
+        Ok(output.concat())
     })
 }
+
+fn install_host<'js>(ctx: &Ctx<'js>, options: &ScriptOptions) -> rquickjs::Result<()> {
+    let host = Object::new(ctx.clone())?;
+    host.set(
+        "stderr",
+        Function::new(ctx.clone(), |s: String| eprint!("{}", s))?,
+    )?;
+    host.set("std", options.std)?;
+    if options.std {
+        host.set(
+            "getenv",
+            Function::new(ctx.clone(), |name: String| std::env::var(name).ok())?,
+        )?;
+        host.set(
+            "loadFile",
+            Function::new(ctx.clone(), |path: String| {
+                std::fs::read_to_string(path).ok()
+            })?,
+        )?;
+    }
+    let globals = ctx.globals();
+    globals.set("__ejs_host", host)?;
+    globals.set("scriptArgs", options.args.clone())?;
+    ctx.eval::<(), _>(SCRIPT_HOST)
+}
+
+/// Run pending jobs, then the earliest due timer, until both queues are empty
+fn run_event_loop(ctx: &Ctx<'_>) -> rquickjs::Result<()> {
+    let timers: Object = ctx.globals().get("__ejs_timers")?;
+    let next: Function = timers.get("next")?;
+    let fire: Function = timers.get("fire")?;
+    loop {
+        while ctx.execute_pending_job() {}
+        let delay: f64 = next.call(())?;
+        if delay < 0.0 {
+            return Ok(());
+        }
+        if delay > 0.0 {
+            std::thread::sleep(Duration::from_secs_f64(delay / 1000.0));
+        }
+        fire.call::<_, ()>(())?;
+    }
+}
+
+/// Forward the runtime's rejection tracking to `__ejs_rejections`
+fn track_rejection<'js>(ctx: Ctx<'js>, promise: Value<'js>, reason: Value<'js>, handled: bool) {
+    let track = ctx
+        .globals()
+        .get::<_, Object>("__ejs_rejections")
+        .and_then(|rejections| rejections.get::<_, Function>("track"));
+    if let Ok(track) = track {
+        let _ = track.call::<_, ()>((promise, reason, handled));
+    }
+}
+
+/// Fail the script if a rejected promise was never handled; the reasons
+/// have already been written to stderr
+fn check_rejections(ctx: &Ctx<'_>) -> Result<(), JsChallengeError> {
+    let unhandled = ctx
+        .globals()
+        .get::<_, Object>("__ejs_rejections")
+        .and_then(|rejections| rejections.get::<_, Function>("report"))
+        .and_then(|report| report.call::<_, u32>(()))
+        .map_err(|e| script_error(ctx, e))?;
+    if unhandled > 0 {
+        return Err(JsChallengeError::JsException(format!(
+            "Script execution error: {} unhandled promise rejection(s)",
+            unhandled
+        )));
+    }
+    Ok(())
+}
+
+fn script_error(ctx: &Ctx<'_>, e: rquickjs::Error) -> JsChallengeError {
+    let err_msg = match &e {
+        rquickjs::Error::Exception => {
+            let exc = ctx.catch();
+            if exc.is_null() || exc.is_undefined() {
+                "Exception (no details)".to_string()
+            } else {
+                format!("Exception: {:?}", exc)
+            }
+        }
+        _ => format!("{:?}", e),
+    };
+    JsChallengeError::JsException(format!("Script execution error: {}", err_msg))
+}
@@ -0,0 +1,67 @@
+//! Preprocessed Player Cache
+//!
+//! Preprocessing dominates the cost of a cold solve, so when
+//! [`SolverConfig::cache_dir`] is set the output is stored on disk, keyed by
+//! a hash of the player and every setting that affects preprocessing.
+
+use crate::builtin::preprocessor::preprocess_player_with;
+use crate::config::SolverConfig;
+use crate::provider::JsChallengeError;
+use crate::registry::challenge_types;
+use crate::trace::{debug, error};
+use std::fs;
+use std::path::Path;
+
+/// Preprocess `player`, reusing a cached result from `config.cache_dir`
+pub(crate) fn preprocess_cached(
+    player: &str,
+    config: &SolverConfig,
+) -> Result<String, JsChallengeError> {
+    let Some(dir) = &config.cache_dir else {
+        return preprocess_player_with(player, config);
+    };
+
+    let path = dir.join(format!("{:016x}.js", cache_key(player, config)));
+    if let Ok(code) = fs::read_to_string(&path) {
+        debug!(path = %path.display(), "Preprocessed player cache hit");
+        return Ok(code);
+    }
+
+    let code = preprocess_player_with(player, config)?;
+    // A cache that cannot be written only costs speed
+    if let Err(_e) = write_atomic(dir, &path, &code) {
+        error!(e = %_e, path = %path.display(), "Failed to write preprocessed player cache");
+    }
+    Ok(code)
+}
+
+fn cache_key(player: &str, config: &SolverConfig) -> u64 {
+    let settings = format!(
+        "ejs {}\npolyfills {:?}\nminify {}\ntypes {}\ndeterministic {:?}\n",
+        env!("CARGO_PKG_VERSION"),
+        config.polyfills,
+        config.minify,
+        challenge_types().join(","),
+        config.deterministic
+    );
+    fnv1a64(&[settings.as_bytes(), player.as_bytes()])
+}
+
+/// Write via a temporary file so concurrent readers never see partial output
+fn write_atomic(dir: &Path, path: &Path, data: &str) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
+}
+
+/// 64-bit FNV-1a over the concatenation of `parts`; stable across builds,
+/// unlike `std`'s default hasher
+pub(crate) fn fnv1a64(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,231 @@
+//! C ABI
+//!
+//! A small C interface over [`Solver`] and [`process_input`] for embedding
+//! ejs in non-Rust programs. Build a `cdylib` or `staticlib` with the
+//! `capi` feature through `cargo rustc --crate-type`; the matching header is
+//! `include/ejs.h`, regenerated with
+//! `cargo run --example update_header --features capi`.
+//!
+//! Strings crossing the boundary are NUL-terminated UTF-8. Strings returned
+//! by ejs are owned by the caller and released with [`ejs_string_free`].
+//! Functions that fail return `NULL` and record a message retrievable with
+//! [`ejs_last_error`] on the same thread.
+
+use crate::config::SolverConfig;
+use crate::director::process_input;
+use crate::provider::{JsChallengeError, JsChallengeInput, JsChallengeOutput, JsChallengeRequest};
+use crate::solver::Solver;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// A preprocessed player with a live runtime, for repeated requests
+///
+/// In-process engines are not thread-safe: use a solver only on the thread
+/// that created it.
+pub struct EjsSolver {
+    inner: RefCell<Solver>,
+}
+
+/// `ejs.h` generated from this module by the build script, for
+/// `examples/update_header.rs`
+///
+/// cbindgen:ignore
+#[doc(hidden)]
+pub const HEADER: &str = include_str!(concat!(env!("OUT_DIR"), "/ejs.h"));
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Preprocess `player_len` bytes of player JavaScript at `player` and start
+/// the configured runtime with the result
+///
+/// `config_json` is a JSON `SolverConfig`, or `NULL` for the defaults.
+/// Returns `NULL` on failure. Release with [`ejs_solver_free`].
+///
+/// # Safety
+///
+/// `player` must point to `player_len` readable bytes and `config_json`
+/// must be `NULL` or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ejs_solver_new(
+    player: *const u8,
+    player_len: usize,
+    config_json: *const c_char,
+) -> *mut EjsSolver {
+    guard(ptr::null_mut(), || {
+        if player.is_null() {
+            return Err(JsChallengeError::InvalidInput("player is NULL".into()));
+        }
+        // SAFETY: the caller guarantees `player_len` readable bytes
+        let bytes = unsafe { std::slice::from_raw_parts(player, player_len) };
+        let player = std::str::from_utf8(bytes)
+            .map_err(|e| JsChallengeError::InvalidInput(format!("player: {}", e)))?;
+        // SAFETY: forwarded from the caller
+        let config = unsafe { parse_config(config_json) }?;
+        let inner = RefCell::new(Solver::new(player, config)?);
+        Ok(Box::into_raw(Box::new(EjsSolver { inner })))
+    })
+}
+
+/// Solve a JSON array of requests (`[{"type": "n", "challenges": [...]}]`)
+/// against the solver's player, reusing its runtime
+///
+/// Returns a JSON `JsChallengeOutput`; per-challenge failures are reported
+/// inside it. Returns `NULL` only if the arguments are invalid.
+///
+/// # Safety
+///
+/// `solver` must come from [`ejs_solver_new`] on the calling thread and
+/// `requests_json` must be a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ejs_solver_solve(
+    solver: *const EjsSolver,
+    requests_json: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        // SAFETY: the caller passes a live solver or NULL
+        let solver = unsafe { solver.as_ref() }
+            .ok_or_else(|| JsChallengeError::InvalidInput("solver is NULL".into()))?;
+        // SAFETY: forwarded from the caller
+        let requests: Vec<JsChallengeRequest> = unsafe { parse_json(requests_json, "requests") }?;
+        let mut inner = solver
+            .inner
+            .try_borrow_mut()
+            .map_err(|_| JsChallengeError::Runtime("solver is already solving".into()))?;
+        let responses = requests.iter().map(|r| inner.solve(r)).collect();
+        to_json(&JsChallengeOutput::result(None, responses))
+    })
+}
+
+/// Process a complete JSON `JsChallengeInput`, as read by the `ejs` CLI
+///
+/// `config_json` is a JSON `SolverConfig`, or `NULL` for the defaults.
+/// Returns a JSON `JsChallengeOutput`, or `NULL` if the arguments are
+/// invalid.
+///
+/// # Safety
+///
+/// `input_json` must be a NUL-terminated string and `config_json` must be
+/// `NULL` or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ejs_process_input(
+    input_json: *const c_char,
+    config_json: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        // SAFETY: forwarded from the caller
+        let input: JsChallengeInput = unsafe { parse_json(input_json, "input") }?;
+        // SAFETY: forwarded from the caller
+        let config = unsafe { parse_config(config_json) }?;
+        to_json(&process_input(input, config))
+    })
+}
+
+/// Release a solver; `NULL` is ignored
+///
+/// # Safety
+///
+/// `solver` must come from [`ejs_solver_new`] and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ejs_solver_free(solver: *mut EjsSolver) {
+    if !solver.is_null() {
+        // SAFETY: the pointer was created by `Box::into_raw` in `ejs_solver_new`
+        drop(unsafe { Box::from_raw(solver) });
+    }
+}
+
+/// Release a string returned by ejs; `NULL` is ignored
+///
+/// # Safety
+///
+/// `s` must come from ejs and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ejs_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        // SAFETY: the pointer was created by `CString::into_raw` in `to_json`
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Message of the last failure on this thread, or `NULL`
+///
+/// The string is owned by ejs and valid until the next ejs call on the
+/// same thread. Release builds abort on panic, so a panic inside ejs ends
+/// the process instead of being reported here.
+#[unsafe(no_mangle)]
+pub extern "C" fn ejs_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// ejs version as a static NUL-terminated string
+#[unsafe(no_mangle)]
+pub extern "C" fn ejs_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Run `f`, recording its error as the last error and returning `fallback`
+/// in that case
+///
+/// Panics are recorded too, but only in builds that unwind: the release
+/// profile sets `panic = "abort"`.
+fn guard<T>(fallback: T, f: impl FnOnce() -> Result<T, JsChallengeError>) -> T {
+    set_last_error(None);
+    let message = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => "ejs panicked".to_string(),
+    };
+    set_last_error(Some(message));
+    fallback
+}
+
+fn set_last_error(message: Option<String>) {
+    let message = message.map(|m| CString::new(m.replace('\0', " ")).unwrap_or_default());
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+/// # Safety
+///
+/// `s` must be `NULL` or a NUL-terminated string.
+unsafe fn parse_json<T: serde::de::DeserializeOwned>(
+    s: *const c_char,
+    what: &str,
+) -> Result<T, JsChallengeError> {
+    if s.is_null() {
+        return Err(JsChallengeError::InvalidInput(format!("{} is NULL", what)));
+    }
+    // SAFETY: the caller guarantees a NUL-terminated string
+    let s = unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|e| JsChallengeError::InvalidInput(format!("{}: {}", what, e)))?;
+    serde_json::from_str(s).map_err(|e| JsChallengeError::InvalidInput(format!("{}: {}", what, e)))
+}
+
+/// # Safety
+///
+/// `s` must be `NULL` or a NUL-terminated string.
+unsafe fn parse_config(s: *const c_char) -> Result<SolverConfig, JsChallengeError> {
+    if s.is_null() {
+        return Ok(SolverConfig::default());
+    }
+    // SAFETY: forwarded from the caller
+    unsafe { parse_json(s, "config") }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<*mut c_char, JsChallengeError> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| JsChallengeError::Runtime(format!("Failed to serialize output: {}", e)))?;
+    // JSON escapes control characters, so it never contains NUL
+    Ok(CString::new(json)
+        .map_err(|e| JsChallengeError::Runtime(e.to_string()))?
+        .into_raw())
+}
@@ -0,0 +1,484 @@
+//! `ejs bench`: time preprocessing stages, runtime start-up and solving
+//! in-process over the test corpus
+//!
+//! The corpus is a manifest (`--corpus`, else the one `Corpus::load`
+//! finds; players downloaded with `cargo run --example download_players`) or a cases file in the format
+//! written by `cargo run --example generate_csv`.
+
+use super::format::EXIT_CHALLENGE_FAILED;
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, parse_runtime, unknown_flag};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use ytdlp_ejs::test_data::Corpus;
+use ytdlp_ejs::trace::{debug, info};
+use ytdlp_ejs::{
+    JsChallengeRequest, JsChallengeResponse, JsChallengeType, RuntimeType, Solver, SolverConfig,
+    preprocess_player_report,
+};
+
+const DEFAULT_WARMUP: usize = 1;
+const DEFAULT_REPEAT: usize = 5;
+
+/// Preprocessing stages in pipeline order; `preprocess` is their total
+const STAGES: [&str; 5] = ["parse", "filter", "extract", "codegen", "preprocess"];
+
+pub fn usage(program: &str) {
+    eprintln!("Usage: {} bench [OPTIONS]", program);
+    eprintln!();
+    eprintln!("Times parse, filter, extract, codegen, runtime init and every solve,");
+    eprintln!("per runtime, over the test corpus.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --compare <runtime>  Benchmark only these runtimes (repeatable)");
+    eprintln!("  --corpus <file>      Corpus manifest (also: EJS_CORPUS)");
+    eprintln!("  --cases <file>       Read cases from a generate_csv file instead");
+    eprintln!("  --players <dir>      Directory holding the player files (default: players)");
+    eprintln!("  --limit <n>          Benchmark only the first <n> players");
+    eprintln!(
+        "  --warmup <n>         Untimed iterations first (default {})",
+        DEFAULT_WARMUP
+    );
+    eprintln!(
+        "  --repeat <n>         Timed iterations (default {})",
+        DEFAULT_REPEAT
+    );
+    eprintln!("  --json               Print the report as JSON");
+    eprintln!("  --help, -h           Show this help message");
+    eprintln!();
+    eprintln!(
+        "Exits with {} when a challenge returns the wrong result.",
+        EXIT_CHALLENGE_FAILED
+    );
+}
+
+// ── Corpus ───────────────────────────────────────────────────────────────────
+
+struct Player {
+    name: String,
+    source: String,
+    cases: Vec<Case>,
+}
+
+struct Case {
+    request: JsChallengeRequest,
+    expected: String,
+}
+
+impl Case {
+    fn new(challenge_type: &str, input: &str, expected: &str) -> Self {
+        Self {
+            request: JsChallengeRequest {
+                challenge_type: JsChallengeType::from(challenge_type),
+                challenges: vec![input.to_string()],
+            },
+            expected: expected.to_string(),
+        }
+    }
+
+    fn passed(&self, response: &JsChallengeResponse) -> bool {
+        match response {
+            JsChallengeResponse::Result { data, .. } => {
+                data.get(&self.request.challenges[0]) == Some(&self.expected)
+            }
+            JsChallengeResponse::Error { .. } => false,
+        }
+    }
+}
+
+/// `(player, type, input, expected)` for every case in `corpus`
+fn corpus_cases(corpus: &Corpus) -> Vec<(String, String, String, String)> {
+    let mut cases = Vec::new();
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let name = format!("{}-{}", player.player, variant);
+            for (challenge_type, steps) in player.steps() {
+                for step in steps {
+                    cases.push((
+                        name.clone(),
+                        challenge_type.to_string(),
+                        step.input.clone(),
+                        step.expected.clone(),
+                    ));
+                }
+            }
+        }
+    }
+    cases
+}
+
+/// Lines of `<player> <type> <input> <expected>`
+fn file_cases(path: &str) -> Result<Vec<(String, String, String, String)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut cases = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [player, challenge_type, input, expected] = fields[..] else {
+            return Err(format!("{}:{}: expected 4 fields", path, i + 1));
+        };
+        cases.push((
+            player.to_string(),
+            challenge_type.to_string(),
+            input.to_string(),
+            expected.to_string(),
+        ));
+    }
+    Ok(cases)
+}
+
+/// Group cases by player, skipping players that are not downloaded
+fn load_corpus(
+    cases: Vec<(String, String, String, String)>,
+    players_dir: &str,
+    limit: Option<usize>,
+) -> Result<Vec<Player>, Box<dyn std::error::Error>> {
+    let mut corpus: Vec<Player> = Vec::new();
+    for (name, challenge_type, input, expected) in cases {
+        let case = Case::new(&challenge_type, &input, &expected);
+        if let Some(player) = corpus.iter_mut().find(|p| p.name == name) {
+            player.cases.push(case);
+            continue;
+        }
+        if limit.is_some_and(|limit| corpus.len() >= limit) {
+            continue;
+        }
+        let path = Path::new(players_dir).join(&name);
+        if !path.exists() {
+            debug!(path = %path.display(), "Skipping missing player");
+            continue;
+        }
+        corpus.push(Player {
+            source: fs::read_to_string(&path)?,
+            name,
+            cases: vec![case],
+        });
+    }
+    Ok(corpus)
+}
+
+// ── Report ───────────────────────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct Report {
+    ejs_version: &'static str,
+    warmup: usize,
+    repeat: usize,
+    players: usize,
+    challenges: usize,
+    /// Preprocessing stages, shared by every runtime
+    stages: BTreeMap<&'static str, Stats>,
+    runtimes: Vec<RuntimeReport>,
+    /// Players that could not be preprocessed, with the error; their
+    /// challenges count as failed for every runtime
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    failed_players: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+struct RuntimeReport {
+    name: &'static str,
+    available: bool,
+    passed: usize,
+    failed: usize,
+    /// `init` and `solve_<type>`
+    timings: BTreeMap<String, Stats>,
+}
+
+/// Summary of timing samples, in milliseconds
+#[derive(Serialize)]
+struct Stats {
+    samples: usize,
+    mean_ms: f64,
+    min_ms: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+}
+
+impl Stats {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+            samples.get(rank.saturating_sub(1)).copied().map_or(0.0, ms)
+        };
+        let total: Duration = samples.iter().sum();
+        Self {
+            samples: samples.len(),
+            mean_ms: if samples.is_empty() {
+                0.0
+            } else {
+                ms(total) / samples.len() as f64
+            },
+            min_ms: samples.first().copied().map_or(0.0, ms),
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p99_ms: percentile(99.0),
+            max_ms: samples.last().copied().map_or(0.0, ms),
+        }
+    }
+}
+
+type Samples<K> = BTreeMap<K, Vec<Duration>>;
+
+// ── Command ──────────────────────────────────────────────────────────────────
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    let mut runtimes = Vec::new();
+    let mut cases_path = None;
+    let mut corpus_path = None;
+    let mut players_dir = "players".to_string();
+    let mut limit = None;
+    let mut warmup = DEFAULT_WARMUP;
+    let mut repeat = DEFAULT_REPEAT;
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compare" => runtimes.push(parse_runtime(&args.value(&arg)?)?),
+            "--cases" => cases_path = Some(args.value(&arg)?),
+            "--corpus" => corpus_path = Some(args.value(&arg)?),
+            "--players" => players_dir = args.value(&arg)?,
+            "--limit" => limit = Some(args.parse(&arg)?),
+            "--warmup" => warmup = args.parse(&arg)?,
+            "--repeat" => repeat = args.parse(&arg)?,
+            "--json" => json = true,
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ => return Err(format!("Unexpected argument '{}'", arg).into()),
+        }
+    }
+    if repeat == 0 {
+        return Err("--repeat must be at least 1".into());
+    }
+    if runtimes.is_empty() {
+        runtimes = RuntimeType::available_runtimes()
+            .into_iter()
+            .filter_map(RuntimeType::parse)
+            .collect();
+    }
+
+    let cases = match (&cases_path, &corpus_path) {
+        (Some(_), Some(_)) => return Err("--cases cannot be combined with --corpus".into()),
+        (Some(path), None) => file_cases(path)?,
+        (None, Some(path)) => corpus_cases(&Corpus::from_file(path)?),
+        (None, None) => corpus_cases(&Corpus::load()?),
+    };
+    let corpus = load_corpus(cases, &players_dir, limit)?;
+    if corpus.is_empty() {
+        return Err(format!(
+            "No player files found in '{}'; run 'cargo run --example download_players'",
+            players_dir
+        )
+        .into());
+    }
+    info!(players = corpus.len(), warmup, repeat, "Starting benchmark");
+
+    let report = bench(&corpus, &config, &runtimes, warmup, repeat)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    if !report.failed_players.is_empty() || report.runtimes.iter().any(|r| r.failed > 0) {
+        std::io::stdout().flush()?;
+        std::process::exit(EXIT_CHALLENGE_FAILED);
+    }
+    Ok(())
+}
+
+fn bench(
+    corpus: &[Player],
+    config: &SolverConfig,
+    runtimes: &[RuntimeType],
+    warmup: usize,
+    repeat: usize,
+) -> Result<Report, Box<dyn std::error::Error>> {
+    let available: Vec<RuntimeType> = runtimes
+        .iter()
+        .copied()
+        .filter(|r| r.is_available())
+        .collect();
+    let mut stages: Samples<&'static str> = BTreeMap::new();
+    let mut timings: Vec<Samples<String>> = vec![BTreeMap::new(); available.len()];
+    let mut passed = vec![0; available.len()];
+    let mut failed = vec![0; available.len()];
+    let mut failed_players = BTreeMap::new();
+
+    for iteration in 0..warmup + repeat {
+        let measured = iteration >= warmup;
+        // Correctness is counted once, on the first timed iteration
+        let counted = iteration == warmup;
+        for player in corpus {
+            let start = Instant::now();
+            let (code, report) = match preprocess_player_report(&player.source, config) {
+                Ok(result) => result,
+                Err(e) => {
+                    if counted {
+                        debug!(player = %player.name, error = %e, "Preprocessing failed");
+                        failed_players.insert(player.name.clone(), e.to_string());
+                        for failed in &mut failed {
+                            *failed += player.cases.len();
+                        }
+                    }
+                    continue;
+                }
+            };
+            let total = start.elapsed();
+            if measured {
+                let durations = [
+                    report.timings.parse,
+                    report.timings.filter,
+                    report.timings.extract,
+                    report.timings.codegen,
+                    total,
+                ];
+                for (stage, duration) in STAGES.into_iter().zip(durations) {
+                    stages.entry(stage).or_default().push(duration);
+                }
+            }
+
+            for (i, runtime) in available.iter().enumerate() {
+                let mut runtime_config = config.clone().with_runtime(*runtime);
+                runtime_config.fallbacks.clear();
+                let start = Instant::now();
+                let solver = Solver::from_preprocessed(code.clone(), runtime_config);
+                let init = start.elapsed();
+                let mut solver = match solver {
+                    Ok(solver) => solver,
+                    Err(_e) => {
+                        debug!(runtime = runtime.name(), player = %player.name, error = %_e, "Runtime failed to start");
+                        if counted {
+                            failed[i] += player.cases.len();
+                        }
+                        continue;
+                    }
+                };
+                if measured {
+                    timings[i].entry("init".into()).or_default().push(init);
+                }
+                for case in &player.cases {
+                    let start = Instant::now();
+                    let response = solver.solve(&case.request);
+                    let elapsed = start.elapsed();
+                    if measured {
+                        let key = format!("solve_{}", case.request.challenge_type.as_str());
+                        timings[i].entry(key).or_default().push(elapsed);
+                    }
+                    if counted {
+                        if case.passed(&response) {
+                            passed[i] += 1;
+                        } else {
+                            failed[i] += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut runtime_reports = Vec::new();
+    for runtime in runtimes {
+        let report = match available.iter().position(|r| r == runtime) {
+            Some(i) => RuntimeReport {
+                name: runtime.name(),
+                available: true,
+                passed: passed[i],
+                failed: failed[i],
+                timings: std::mem::take(&mut timings[i])
+                    .into_iter()
+                    .map(|(key, samples)| (key, Stats::from_samples(samples)))
+                    .collect(),
+            },
+            None => RuntimeReport {
+                name: runtime.name(),
+                available: false,
+                passed: 0,
+                failed: 0,
+                timings: BTreeMap::new(),
+            },
+        };
+        runtime_reports.push(report);
+    }
+
+    Ok(Report {
+        ejs_version: env!("CARGO_PKG_VERSION"),
+        warmup,
+        repeat,
+        players: corpus.len(),
+        challenges: corpus.iter().map(|p| p.cases.len()).sum(),
+        stages: stages
+            .into_iter()
+            .map(|(stage, samples)| (stage, Stats::from_samples(samples)))
+            .collect(),
+        runtimes: runtime_reports,
+        failed_players,
+    })
+}
+
+fn print_report(report: &Report) {
+    println!(
+        "{} players, {} challenges, {} warmup + {} timed iterations",
+        report.players, report.challenges, report.warmup, report.repeat
+    );
+    println!();
+    println!(
+        "{:<18} {:>8} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "metric", "samples", "mean ms", "p50 ms", "p90 ms", "p99 ms", "max ms"
+    );
+    let row = |name: &str, stats: &Stats| {
+        println!(
+            "{:<18} {:>8} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3}",
+            name,
+            stats.samples,
+            stats.mean_ms,
+            stats.p50_ms,
+            stats.p90_ms,
+            stats.p99_ms,
+            stats.max_ms
+        );
+    };
+    for stage in STAGES {
+        if let Some(stats) = report.stages.get(stage) {
+            row(stage, stats);
+        }
+    }
+    for runtime in &report.runtimes {
+        for (metric, stats) in &runtime.timings {
+            row(&format!("{} {}", runtime.name, metric), stats);
+        }
+    }
+
+    println!();
+    for (player, error) in &report.failed_players {
+        println!("{}: preprocessing failed: {}", player, error);
+    }
+    for runtime in &report.runtimes {
+        if runtime.available {
+            println!(
+                "{}: {}/{} passed",
+                runtime.name,
+                runtime.passed,
+                runtime.passed + runtime.failed
+            );
+        } else {
+            println!("{}: skipped (not available)", runtime.name);
+        }
+    }
+}
@@ -0,0 +1,267 @@
+//! `ejs corpus add`: record a new player in the test corpus
+//!
+//! The player file is stored at its `get_cache_path` location, generated
+//! challenges are solved with one or more reference runtimes, and the
+//! results they agree on are appended to the corpus manifest. Vectors
+//! already recorded for the player are solved too, so a new variant that
+//! disagrees with them is rejected.
+//!
+//! Every runtime solves the output of ejs's own preprocessor, so the
+//! runtimes only cross-check each other: a preprocessing bug that yields
+//! plausible results is recorded as expected. Check new vectors against an
+//! independent solver, such as yt-dlp's, before committing them.
+
+use super::verify::{clock_seed, random_requests};
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, parse_runtime, unknown_flag};
+use std::fs;
+use std::path::{Path, PathBuf};
+use ytdlp_ejs::test_data::{
+    ALL_VARIANTS, CORPUS_ENV, Corpus, CorpusStep, MANIFEST_PATH, get_cache_path,
+};
+use ytdlp_ejs::trace::info;
+use ytdlp_ejs::{
+    JsChallengeResponse, RuntimeType, Solver, SolverConfig, Strictness, preprocess_player_with,
+};
+
+/// Generated challenges per type
+const DEFAULT_COUNT: usize = 2;
+
+pub fn usage(program: &str) {
+    eprintln!(
+        "Usage: {} corpus add [OPTIONS] --id <player> <player-file>",
+        program
+    );
+    eprintln!();
+    eprintln!("Stores the player under players/, solves generated challenges with the");
+    eprintln!("reference runtimes and appends the agreed results to the corpus manifest.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --id <player>        Player id, e.g. 3d3ba064 (required)");
+    eprintln!(
+        "  --variant <variant>  Player variant (default main): {}",
+        ALL_VARIANTS.join(", ")
+    );
+    eprintln!("  --compare <runtime>  Reference runtime; repeat to require agreement");
+    eprintln!("                       (default: the first available runtime)");
+    eprintln!(
+        "  --count <n>          Generated challenges per type (default {})",
+        DEFAULT_COUNT
+    );
+    eprintln!("  --random-seed <n>    Seed for generated challenges (default: the clock)");
+    eprintln!(
+        "  --manifest <file>    Manifest to update (default: ${} or {})",
+        CORPUS_ENV, MANIFEST_PATH
+    );
+    eprintln!("  --force              Replace a different file stored for the player");
+    eprintln!("  --help, -h           Show this help message");
+    eprintln!();
+    eprintln!("Every runtime solves ejs's own preprocessed player, so a preprocessing bug");
+    eprintln!("is recorded as expected; check new vectors with an independent solver.");
+}
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    match args.next().as_deref() {
+        Some("add") => {}
+        Some("--help" | "-h") | None => {
+            usage(program);
+            return Ok(());
+        }
+        Some(other) => return Err(format!("Unknown corpus command '{}' (add)", other).into()),
+    }
+
+    let mut id = None;
+    let mut variant = "main".to_string();
+    let mut runtimes = Vec::new();
+    let mut count = DEFAULT_COUNT;
+    let mut seed = None;
+    let mut manifest = None;
+    let mut force = false;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--id" => id = Some(args.value(&arg)?),
+            "--variant" => variant = args.value(&arg)?,
+            "--compare" => runtimes.push(parse_runtime(&args.value(&arg)?)?),
+            "--count" => count = args.parse(&arg)?,
+            "--random-seed" => seed = Some(args.parse::<u64>(&arg)?),
+            "--manifest" => manifest = Some(PathBuf::from(args.value(&arg)?)),
+            "--force" => force = true,
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let [player_path] = positional.as_slice() else {
+        return Err("Expected exactly one player file".into());
+    };
+    let id = id.ok_or("--id is required")?;
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid player id '{}'", id).into());
+    }
+    if !ALL_VARIANTS.contains(&variant.as_str()) {
+        return Err(format!(
+            "Unknown variant '{}' ({})",
+            variant,
+            ALL_VARIANTS.join(", ")
+        )
+        .into());
+    }
+    if runtimes.is_empty() {
+        let default = RuntimeType::available_runtimes()
+            .into_iter()
+            .filter_map(RuntimeType::parse)
+            .find(|r| r.is_available())
+            .ok_or("No runtime is available")?;
+        runtimes.push(default);
+    }
+    let manifest = manifest
+        .or_else(|| std::env::var_os(CORPUS_ENV).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(MANIFEST_PATH));
+    let mut corpus = if manifest.exists() {
+        Corpus::from_file(&manifest)?
+    } else {
+        Corpus { players: vec![] }
+    };
+
+    // Generated challenges, then the ones already recorded for the player
+    let seed = seed.unwrap_or_else(clock_seed);
+    info!(seed, "Generating challenges");
+    let mut requests = random_requests(count, seed);
+    // (n, sig), like the requests
+    let recorded = match corpus.players.iter().find(|p| p.player == id) {
+        Some(player) => [player.n.clone(), player.sig.clone()],
+        None => Default::default(),
+    };
+    for (request, steps) in requests.iter_mut().zip(&recorded) {
+        request
+            .challenges
+            .extend(steps.iter().map(|s| s.input.clone()));
+    }
+
+    let source = fs::read_to_string(player_path)?;
+    config.strictness = Strictness::Strict;
+    let preprocessed = preprocess_player_with(&source, &config)?;
+
+    // Results per request, then per challenge, from every runtime
+    let mut agreed: Vec<Vec<CorpusStep>> = vec![Vec::new(); requests.len()];
+    let mut problems = Vec::new();
+    let mut columns = Vec::new();
+    for runtime in &runtimes {
+        let mut config = config.clone().with_runtime(*runtime);
+        config.fallbacks.clear();
+        info!(runtime = runtime.name(), "Solving with reference runtime");
+        let mut solver = Solver::from_preprocessed(preprocessed.clone(), config)
+            .map_err(|e| format!("{}: {}", runtime.name(), e))?;
+        let responses: Vec<_> = requests.iter().map(|r| solver.solve(r)).collect();
+        columns.push((runtime.name(), responses));
+    }
+    for (i, request) in requests.iter().enumerate() {
+        let challenge_type = request.challenge_type.as_str();
+        for challenge in &request.challenges {
+            let results: Vec<(&str, Result<String, String>)> = columns
+                .iter()
+                .map(|(name, responses)| (*name, outcome(&responses[i], challenge)))
+                .collect();
+            let first = &results[0].1;
+            let recorded = recorded[i].iter().find(|s| &s.input == challenge);
+            match first {
+                Err(e) => problems.push(format!(
+                    "{} {}: {} failed: {}",
+                    challenge_type, challenge, results[0].0, e
+                )),
+                Ok(_) if results.iter().any(|(_, r)| r != first) => {
+                    let summary: Vec<String> = results
+                        .iter()
+                        .map(|(name, r)| match r {
+                            Ok(value) => format!("{}={}", name, value),
+                            Err(e) => format!("{} failed: {}", name, e),
+                        })
+                        .collect();
+                    problems.push(format!(
+                        "{} {}: runtimes disagree: {}",
+                        challenge_type,
+                        challenge,
+                        summary.join(", ")
+                    ));
+                }
+                Ok(value) => match recorded {
+                    Some(step) if &step.expected != value => problems.push(format!(
+                        "{} {}: got {}, but {} is recorded",
+                        challenge_type, challenge, value, step.expected
+                    )),
+                    Some(_) => {}
+                    None => agreed[i].push(CorpusStep {
+                        input: challenge.clone(),
+                        expected: value.clone(),
+                    }),
+                },
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(format!(
+            "Not adding {} ({}):\n  {}",
+            id,
+            variant,
+            problems.join("\n  ")
+        )
+        .into());
+    }
+
+    store_player(&source, &get_cache_path(&id, &variant), force)?;
+    let sig = agreed.pop().unwrap_or_default();
+    let n = agreed.pop().unwrap_or_default();
+    let (added_n, added_sig) = corpus.add(&id, &variant, n, sig);
+    corpus.save(&manifest)?;
+    println!(
+        "Added {} n and {} sig vectors for {} ({}) to {}",
+        added_n,
+        added_sig,
+        id,
+        variant,
+        manifest.display()
+    );
+    Ok(())
+}
+
+/// Result of `challenge` in `response`, or why there is none
+fn outcome(response: &JsChallengeResponse, challenge: &str) -> Result<String, String> {
+    match response {
+        JsChallengeResponse::Result { data, errors, .. } => match data.get(challenge) {
+            Some(value) => Ok(value.clone()),
+            None => Err(errors
+                .get(challenge)
+                .map_or_else(|| "no result returned".into(), |f| f.message.clone())),
+        },
+        JsChallengeResponse::Error { error, .. } => Err(error.clone()),
+    }
+}
+
+/// Copy the player to `cache_path`, refusing to replace a different file
+/// unless `force` is set
+fn store_player(source: &str, cache_path: &str, force: bool) -> CliResult {
+    let path = Path::new(cache_path);
+    if let Ok(existing) = fs::read_to_string(path)
+        && existing != source
+        && !force
+    {
+        return Err(format!(
+            "{} already holds a different player (use --force to replace it)",
+            cache_path
+        )
+        .into());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, source)?;
+    Ok(())
+}
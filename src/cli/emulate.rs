@@ -0,0 +1,226 @@
+//! Runtime identity emulation: answer the version probes and accept the
+//! command lines of `deno`, `node` and `bun`
+//!
+//! yt-dlp prefers those runtimes over QuickJS and invokes each with its own
+//! flags and script-delivery method. The identity is taken from the program
+//! name (install `ejs` as `deno`, `node` or `bun`) or from
+//! `ejs --emulate <name> ...`. Scripts go through the same jsc() fast path
+//! as `--script`; runtime flags that do not affect evaluation are ignored.
+
+use super::script::{self, Invocation};
+use super::{Args, CliResult};
+use std::path::Path;
+use ytdlp_ejs::SolverConfig;
+
+/// Runtime whose command line ejs answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Identity {
+    Deno,
+    Node,
+    Bun,
+}
+
+impl Identity {
+    pub const NAMES: &[&str] = &["deno", "node", "bun"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "deno" => Some(Self::Deno),
+            "node" | "nodejs" => Some(Self::Node),
+            "bun" => Some(Self::Bun),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Deno => "deno",
+            Self::Node => "node",
+            Self::Bun => "bun",
+        }
+    }
+
+    /// Identity implied by argv[0], e.g. `/usr/local/bin/deno` or `node.exe`
+    pub fn from_program(program: &str) -> Option<Self> {
+        Path::new(program)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(Self::parse)
+    }
+
+    /// `--version` output; recent enough that yt-dlp does not warn
+    fn version(self) -> String {
+        let ejs = env!("CARGO_PKG_VERSION");
+        match self {
+            Self::Deno => format!(
+                "deno 2.4.0 (stable, release, ejs {})\nv8 13.7.152.6\ntypescript 5.8.3",
+                ejs
+            ),
+            Self::Node => "v22.17.0".into(),
+            Self::Bun => "1.2.18".into(),
+        }
+    }
+
+    fn is_version_flag(self, flag: &str) -> bool {
+        match self {
+            Self::Deno => matches!(flag, "--version" | "-V"),
+            Self::Node | Self::Bun => matches!(flag, "--version" | "-v"),
+        }
+    }
+
+    /// Ignored flags whose value is a separate argument
+    fn value_flags(self) -> &'static [&'static str] {
+        match self {
+            Self::Deno => &[
+                "-c",
+                "--config",
+                "--import-map",
+                "--lock",
+                "--cert",
+                "--location",
+                "--seed",
+                "-L",
+                "--log-level",
+            ],
+            Self::Node => &[
+                "-r",
+                "--require",
+                "--import",
+                "--loader",
+                "-C",
+                "--conditions",
+                "--title",
+            ],
+            Self::Bun => &[
+                "-r",
+                "--preload",
+                "--cwd",
+                "-c",
+                "--config",
+                "--tsconfig-override",
+                "-d",
+                "--define",
+                "-l",
+                "--loader",
+                "--conditions",
+            ],
+        }
+    }
+
+    /// Subcommands that take a script, e.g. `deno run -`
+    fn run_commands(self) -> &'static [&'static str] {
+        match self {
+            Self::Deno => &["run"],
+            Self::Node => &[],
+            Self::Bun => &["run"],
+        }
+    }
+}
+
+/// Identity requested by argv[0] or `--emulate <name>`, and the index of
+/// the first argument meant for the emulated runtime
+pub fn detect(args: &[String]) -> Result<Option<(Identity, usize)>, String> {
+    if let Some(identity) = args
+        .first()
+        .and_then(|program| Identity::from_program(program))
+    {
+        return Ok(Some((identity, 1)));
+    }
+    if args.get(1).map(String::as_str) != Some("--emulate") {
+        return Ok(None);
+    }
+    let name = args.get(2).ok_or("--emulate requires an argument")?;
+    let identity = Identity::parse(name).ok_or_else(|| {
+        format!(
+            "Unknown runtime identity '{}' ({})",
+            name,
+            Identity::NAMES.join(", ")
+        )
+    })?;
+    Ok(Some((identity, 3)))
+}
+
+/// Run `args` (without the program name) as `identity` would
+pub fn run(identity: Identity, args: Args, config: SolverConfig) -> CliResult {
+    match parse(identity, args)? {
+        Some(inv) => script::execute(inv, config),
+        None => {
+            println!("{}", identity.version());
+            Ok(())
+        }
+    }
+}
+
+/// `None` for a version probe
+fn parse(identity: Identity, mut args: Args) -> Result<Option<Invocation>, String> {
+    let mut inv = Invocation::default();
+    let mut command_seen = false;
+    let mut print = false;
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if arg.starts_with("--") => (flag, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match flag {
+            flag if identity.is_version_flag(flag) => return Ok(None),
+            "-e" | "--eval" if identity != Identity::Deno => {
+                inv.expr = Some(args.value(flag)?);
+            }
+            "-p" | "--print" if identity != Identity::Deno => {
+                inv.expr = Some(args.value(flag)?);
+                print = true;
+            }
+            "--input-type" if identity == Identity::Node => {
+                let value = match inline_value {
+                    Some(value) => value.to_string(),
+                    None => args.value(flag)?,
+                };
+                inv.module = value == "module";
+            }
+            "--ext" if identity == Identity::Deno => {
+                let value = match inline_value {
+                    Some(value) => value.to_string(),
+                    None => args.value(flag)?,
+                };
+                inv.module = value == "mjs";
+            }
+            "--" => inv.file = args.next(),
+            "-" => inv.file = Some(arg),
+            flag if identity.value_flags().contains(&flag) && inline_value.is_none() => {
+                args.value(flag)?;
+            }
+            // Permissions, caching, warnings and the like
+            flag if flag.starts_with('-') => {}
+            // `deno eval [-p] <code>`
+            "eval" if identity == Identity::Deno && !command_seen => {
+                command_seen = true;
+                let code = args.find(|arg| {
+                    print |= matches!(arg.as_str(), "-p" | "--print");
+                    !arg.starts_with('-')
+                });
+                inv.expr = Some(code.ok_or("eval requires code")?);
+            }
+            command if identity.run_commands().contains(&command) && !command_seen => {
+                command_seen = true;
+            }
+            _ => inv.file = Some(arg),
+        }
+        // Everything after the script is passed to it
+        if inv.file.is_some() || (identity == Identity::Deno && inv.expr.is_some()) {
+            break;
+        }
+    }
+    inv.args.extend(args);
+
+    if print && let Some(expr) = inv.expr.take() {
+        let quoted = serde_json::to_string(&expr).map_err(|e| e.to_string())?;
+        inv.expr = Some(format!("console.log((0, eval)({}));", quoted));
+    }
+    if inv.expr.is_none() && inv.file.is_none() {
+        return Err(format!("{} emulation: no script given", identity.name()));
+    }
+    if inv.file.as_deref().is_some_and(|f| f.ends_with(".mjs")) {
+        inv.module = true;
+    }
+    Ok(Some(inv))
+}
@@ -0,0 +1,173 @@
+//! Output formats for `ejs solve`: `--format json|pretty|lines|tsv`
+
+use ytdlp_ejs::{
+    JsChallengeErrorKind, JsChallengeFailure, JsChallengeOutput, JsChallengeRequest,
+    JsChallengeResponse,
+};
+
+pub const FORMAT_OPTION: &str =
+    "  --format <f>         Output format: json (default), pretty, lines, tsv";
+
+/// Exit code when the output was printed but some challenge failed
+pub const EXIT_CHALLENGE_FAILED: i32 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Compact `JsChallengeOutput`, as yt-dlp expects
+    #[default]
+    Json,
+    /// Indented `JsChallengeOutput`
+    Pretty,
+    /// One `type challenge result` line per challenge
+    Lines,
+    /// Tab-separated rows with a header, for spreadsheets
+    Tsv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "pretty" => Some(Self::Pretty),
+            "lines" => Some(Self::Lines),
+            "tsv" => Some(Self::Tsv),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of one challenge
+enum Outcome<'a> {
+    Solved(&'a str),
+    /// Solved, but the result failed a plausibility check
+    Suspicious(&'a str, &'a JsChallengeFailure),
+    Failed(JsChallengeErrorKind, &'a str),
+}
+
+struct Row<'a> {
+    challenge_type: &'a str,
+    challenge: &'a str,
+    outcome: Outcome<'a>,
+}
+
+/// Pair every requested challenge with its outcome in `output`
+fn rows<'a>(requests: &'a [JsChallengeRequest], output: &'a JsChallengeOutput) -> Vec<Row<'a>> {
+    let mut rows = Vec::new();
+    for (i, request) in requests.iter().enumerate() {
+        for challenge in &request.challenges {
+            let outcome = match output {
+                JsChallengeOutput::Error { error, kind, .. } => Outcome::Failed(*kind, error),
+                JsChallengeOutput::Result { responses, .. } => match responses.get(i) {
+                    Some(JsChallengeResponse::Result {
+                        data,
+                        errors,
+                        warnings,
+                    }) => match (data.get(challenge), errors.get(challenge)) {
+                        (Some(result), _) => match warnings.get(challenge) {
+                            Some(warning) => Outcome::Suspicious(result, warning),
+                            None => Outcome::Solved(result),
+                        },
+                        (None, Some(failure)) => Outcome::Failed(failure.kind, &failure.message),
+                        (None, None) => {
+                            Outcome::Failed(JsChallengeErrorKind::Internal, "No result returned")
+                        }
+                    },
+                    Some(JsChallengeResponse::Error { error, kind, .. }) => {
+                        Outcome::Failed(*kind, error)
+                    }
+                    None => Outcome::Failed(JsChallengeErrorKind::Internal, "No response returned"),
+                },
+            };
+            rows.push(Row {
+                challenge_type: request.challenge_type.as_str(),
+                challenge,
+                outcome,
+            });
+        }
+    }
+    rows
+}
+
+/// Whether any challenge in `output` failed
+pub fn any_failed(requests: &[JsChallengeRequest], output: &JsChallengeOutput) -> bool {
+    matches!(output, JsChallengeOutput::Error { .. })
+        || rows(requests, output)
+            .iter()
+            .any(|row| matches!(row.outcome, Outcome::Failed(..)))
+}
+
+/// Render `output` for `requests` in `format`
+pub fn render(
+    format: Format,
+    requests: &[JsChallengeRequest],
+    output: &JsChallengeOutput,
+) -> serde_json::Result<String> {
+    let text = match format {
+        Format::Json => serde_json::to_string(output)?,
+        Format::Pretty => serde_json::to_string_pretty(output)?,
+        Format::Lines => {
+            let mut lines = Vec::new();
+            for row in rows(requests, output) {
+                let value = match row.outcome {
+                    Outcome::Solved(result) | Outcome::Suspicious(result, _) => result.to_string(),
+                    Outcome::Failed(kind, message) => {
+                        format!("error:{} {}", kind_name(kind), one_line(message))
+                    }
+                };
+                lines.push(format!(
+                    "{} {} {}",
+                    row.challenge_type, row.challenge, value
+                ));
+            }
+            // A failure before any challenge was attempted
+            if lines.is_empty()
+                && let JsChallengeOutput::Error { error, kind, .. } = output
+            {
+                lines.push(format!("error:{} {}", kind_name(*kind), one_line(error)));
+            }
+            lines.join("\n")
+        }
+        Format::Tsv => {
+            let mut lines = vec!["type\tchallenge\tresult\tstatus\tmessage".to_string()];
+            for row in rows(requests, output) {
+                let (result, status, message) = match row.outcome {
+                    Outcome::Solved(result) => (result, "ok".to_string(), String::new()),
+                    Outcome::Suspicious(result, warning) => {
+                        (result, "warning".to_string(), one_line(&warning.message))
+                    }
+                    Outcome::Failed(kind, message) => ("", kind_name(kind), one_line(message)),
+                };
+                lines.push(
+                    [
+                        row.challenge_type,
+                        row.challenge,
+                        result,
+                        status.as_str(),
+                        message.as_str(),
+                    ]
+                    .join("\t"),
+                );
+            }
+            if lines.len() == 1
+                && let JsChallengeOutput::Error { error, kind, .. } = output
+            {
+                lines.push(format!("\t\t\t{}\t{}", kind_name(*kind), one_line(error)));
+            }
+            lines.join("\n")
+        }
+    };
+    Ok(text)
+}
+
+/// The `snake_case` name used in JSON, e.g. `js_exception`
+pub(super) fn kind_name(kind: JsChallengeErrorKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Collapse tabs and newlines so a message stays in its column
+fn one_line(message: &str) -> String {
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,84 @@
+//! `ejs inspect`: preprocess a player and report the outcome as JSON
+
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, unknown_flag};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::Instant;
+use ytdlp_ejs::{JsChallengeFailure, SolverConfig, preprocess_player_report};
+
+pub fn usage(program: &str) {
+    eprintln!("Usage: {} inspect [OPTIONS] <player>", program);
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --help, -h           Show this help message");
+}
+
+#[derive(Serialize)]
+struct Report {
+    player: String,
+    size: usize,
+    preprocess_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    preprocessed_size: Option<usize>,
+    /// Solver functions found, in source order
+    solvers: Vec<String>,
+    /// Candidate count per challenge type
+    candidates: BTreeMap<String, usize>,
+    /// Custom extractors that failed, with their error
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    extractor_errors: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsChallengeFailure>,
+}
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    let mut player_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ if player_path.is_none() => player_path = Some(arg),
+            _ => return Err(format!("Unexpected argument '{}'", arg).into()),
+        }
+    }
+    let player_path = player_path.ok_or("Missing player file argument")?;
+    let player = fs::read_to_string(&player_path)?;
+
+    let start = Instant::now();
+    let result = preprocess_player_report(&player, &config);
+    let preprocess_ms = start.elapsed().as_millis();
+
+    let report = match result {
+        Ok((code, found)) => Report {
+            player: player_path,
+            size: player.len(),
+            preprocess_ms,
+            preprocessed_size: Some(code.len()),
+            solvers: found.solvers,
+            candidates: found.candidates,
+            extractor_errors: found.extractor_errors,
+            error: None,
+        },
+        Err(e) => Report {
+            player: player_path,
+            size: player.len(),
+            preprocess_ms,
+            preprocessed_size: None,
+            solvers: Vec::new(),
+            candidates: BTreeMap::new(),
+            extractor_errors: BTreeMap::new(),
+            error: Some(JsChallengeFailure::from(&e)),
+        },
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
@@ -0,0 +1,301 @@
+//! Command-Line Interface
+//!
+//! `ejs <command> [options]`. Arguments that do not start with a known
+//! command are read as `ejs solve`, so the original positional form
+//! (`ejs [options] <player> <type>:<challenge> ...`) and `--script` keep
+//! working for yt-dlp.
+
+pub mod bench;
+pub mod corpus;
+pub mod emulate;
+pub mod format;
+pub mod inspect;
+pub mod preprocess;
+pub mod script;
+pub mod serve;
+pub mod solve;
+pub mod verify;
+
+use std::error::Error;
+use ytdlp_ejs::{Capabilities, PolyfillProfile, RuntimeType, SolverConfig, Strictness};
+
+pub type CliResult = Result<(), Box<dyn Error>>;
+
+/// Subcommands and their one-line summaries, in help order
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("solve", "Solve challenges against a player (default)"),
+    (
+        "preprocess",
+        "Write the preprocessed player for 'solve --preprocessed'",
+    ),
+    ("inspect", "Preprocess a player and report what was found"),
+    ("serve", "Answer JSON inputs read line by line from stdin"),
+    ("verify", "Compare the results of every available runtime"),
+    (
+        "bench",
+        "Time preprocessing and solving over the test corpus",
+    ),
+    ("corpus", "Add a player and its vectors to the test corpus"),
+    ("schema", "Print the JSON Schema of the protocol"),
+    ("capabilities", "Print protocol versions and runtimes"),
+    ("help", "Show help for a command"),
+];
+
+/// Options shared by every command that solves or preprocesses
+pub const CONFIG_OPTIONS: &str = "\
+  --runtime <runtime>  JavaScript runtime to use
+  --fallback <runtime> Runtime to try if the previous one is unavailable
+  --config <file>      Load a JSON SolverConfig (also: EJS_CONFIG)
+  --timeout <ms>       Time limit per challenge
+  --memory-limit <n>   Heap limit in bytes
+  --cache-dir <dir>    Cache preprocessed players in <dir>
+  --polyfills <p>      Polyfill profile: full, minimal, none
+  --minify             Minify preprocessed code
+  --strictness <s>     Suspicious results: off, warn, strict
+  --deterministic      Seeded Math.random, frozen clock, inert timers
+  --seed <n>           Math.random seed (implies --deterministic)
+  --clock <ms>         Frozen Date.now() value (implies --deterministic)";
+
+pub fn print_usage(program: &str) {
+    // yt-dlp detects QuickJS-compatible runtimes by parsing `--help` output.
+    // It runs `{exe} --help` and matches: r'^QuickJS(?:-ng)?\s+version\s+(\S+)'
+    // We report as quickjs-ng v0.12.0 (the minimum recommended) so yt-dlp
+    // does not emit a "versions older than ..." warning.
+    eprintln!(
+        "QuickJS-ng version 0.12.0  (ejs {})",
+        env!("CARGO_PKG_VERSION")
+    );
+    eprintln!();
+    eprintln!("Usage: {} <command> [OPTIONS] ...", program);
+    eprintln!(
+        "       {} [OPTIONS] <player> [<type>:<request> ...]",
+        program
+    );
+    eprintln!("       {} [OPTIONS] --input <file|->", program);
+    eprintln!("       {} --script <file>", program);
+    eprintln!("       {} --emulate <deno|node|bun> ...", program);
+    eprintln!(
+        "       {} [-m] [--std] [-e <expr>] [<file>|-] [args ...]",
+        program
+    );
+    eprintln!();
+    eprintln!("Commands:");
+    for (name, summary) in COMMANDS {
+        eprintln!("  {:<14} {}", name, summary);
+    }
+    eprintln!();
+    eprintln!("Runtimes: {}", RuntimeType::available_runtimes().join(", "));
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --output-preprocessed  Include the preprocessed player in the output");
+    eprintln!("{}", format::FORMAT_OPTION);
+    eprintln!("  --input <file|->     Answer a JSON JsChallengeInput from <file> or stdin");
+    eprintln!("  --script <file>      Execute JS file via embedded QuickJS (qjs compat)");
+    eprintln!("  -e, --eval <expr>    Evaluate <expr> instead of a script file");
+    eprintln!("  -m, --module         Evaluate the script as an ES module");
+    eprintln!("  --std                Provide a minimal qjs 'std' global");
+    eprintln!("  --stack-size <n>     JS stack limit in bytes for scripts");
+    eprintln!("  --emulate <runtime>  Answer the command line of deno, node or bun");
+    eprintln!("  --capabilities       Print supported protocol versions, runtimes and features");
+    eprintln!("  --help, -h           Show this help message");
+    eprintln!("  --version, -V        Print version");
+    eprintln!();
+    eprintln!("Examples:");
+    eprintln!("  {} player.js n:ZdZIqFPQK-Ty8wId", program);
+    eprintln!(
+        "  {} solve --runtime deno player.js sig:gN7a-hudCuAuPH6f...",
+        program
+    );
+    eprintln!("  {} --script solver_program.js", program);
+    eprintln!();
+    eprintln!("Run '{} help <command>' for command options.", program);
+}
+
+/// `ejs help [command]`
+pub fn help(program: &str, command: Option<&str>) -> CliResult {
+    match command {
+        None => print_usage(program),
+        Some("solve") => solve::usage(program),
+        Some("preprocess") => preprocess::usage(program),
+        Some("inspect") => inspect::usage(program),
+        Some("serve") => serve::usage(program),
+        Some("verify") => verify::usage(program),
+        Some("bench") => bench::usage(program),
+        Some("corpus") => corpus::usage(program),
+        Some("schema") => eprintln!("Usage: {} schema [input|output|capabilities]", program),
+        Some("capabilities") => eprintln!("Usage: {} capabilities", program),
+        Some("help") => eprintln!("Usage: {} help [command]", program),
+        Some(other) => return Err(format!("Unknown command '{}'", other).into()),
+    }
+    Ok(())
+}
+
+// ── Argument parsing ─────────────────────────────────────────────────────────
+
+/// Remaining command-line arguments
+pub struct Args {
+    args: std::vec::IntoIter<String>,
+}
+
+impl Args {
+    pub fn new(args: Vec<String>) -> Self {
+        Self {
+            args: args.into_iter(),
+        }
+    }
+
+    /// The value following `flag`
+    pub fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.args
+            .next()
+            .ok_or_else(|| format!("{} requires an argument", flag))
+    }
+
+    /// The value following `flag`, parsed
+    pub fn parse<T: std::str::FromStr>(&mut self, flag: &str) -> Result<T, String>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let value = self.value(flag)?;
+        value.parse().map_err(|e| format!("{}: {}", flag, e))
+    }
+}
+
+/// Apply `flag` if it is one of [`CONFIG_OPTIONS`]; returns whether it was
+pub fn config_flag(flag: &str, args: &mut Args, config: &mut SolverConfig) -> Result<bool, String> {
+    match flag {
+        "--runtime" => config.runtime = Some(parse_runtime(&args.value(flag)?)?.into()),
+        "--fallback" => {
+            let runtime = parse_runtime(&args.value(flag)?)?;
+            config.fallbacks.push(runtime.into());
+        }
+        // Already applied by `load_config`
+        "--config" => {
+            args.value(flag)?;
+        }
+        "--timeout" => config.timeout_ms = Some(args.parse(flag)?),
+        "--memory-limit" => config.memory_limit = Some(args.parse(flag)?),
+        "--cache-dir" => config.cache_dir = Some(args.value(flag)?.into()),
+        "--polyfills" => {
+            let name = args.value(flag)?;
+            config.polyfills = PolyfillProfile::parse(&name).ok_or_else(|| {
+                format!("Unknown polyfill profile '{}' (full, minimal, none)", name)
+            })?;
+        }
+        "--minify" => config.minify = true,
+        "--strictness" => {
+            let name = args.value(flag)?;
+            config.strictness = Strictness::parse(&name)
+                .ok_or_else(|| format!("Unknown strictness '{}' (off, warn, strict)", name))?;
+        }
+        "--deterministic" => {
+            config.deterministic.get_or_insert_default();
+        }
+        "--seed" => config.deterministic.get_or_insert_default().seed = args.parse(flag)?,
+        "--clock" => config.deterministic.get_or_insert_default().now_ms = args.parse(flag)?,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+impl Iterator for Args {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.args.next()
+    }
+}
+
+/// The arguments ejs reads options from, without the program name: none in
+/// script mode, where arguments after the script belong to it, and none
+/// after `--`
+pub fn own_args(args: &[String]) -> &[String] {
+    let args = args.get(1..).unwrap_or_default();
+    if script::selects(args) {
+        return &[];
+    }
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    &args[..end]
+}
+
+/// Whether `args` (from [`own_args`]) only ask for help or version output,
+/// which must not depend on a valid configuration
+pub fn wants_help(args: &[String]) -> bool {
+    matches!(
+        args.first().map(String::as_str),
+        Some("help" | "capabilities" | "schema")
+    ) || args.iter().any(|arg| {
+        matches!(
+            arg.as_str(),
+            "--help" | "-h" | "--version" | "-V" | "--capabilities"
+        )
+    })
+}
+
+/// Config file (`--config` in `args` or `EJS_CONFIG`) overlaid with `EJS_*`
+/// variables; command-line flags are applied on top by each command
+pub fn load_config(args: &[String]) -> Result<SolverConfig, Box<dyn Error>> {
+    let config_path = args
+        .iter()
+        .position(|arg| arg == "--config")
+        .and_then(|i| args.get(i + 1));
+    let config = match config_path {
+        Some(path) => SolverConfig::from_file(path)?.with_env()?,
+        None => SolverConfig::from_env()?,
+    };
+    Ok(config)
+}
+
+pub fn parse_runtime(name: &str) -> Result<RuntimeType, String> {
+    RuntimeType::parse(name).ok_or_else(|| {
+        format!(
+            "Unknown runtime '{}'. Available: {}",
+            name,
+            RuntimeType::available_runtimes().join(", ")
+        )
+    })
+}
+
+/// Error for a flag no command option matches
+pub fn unknown_flag(flag: &str) -> Box<dyn Error> {
+    format!("Unknown option '{}'", flag).into()
+}
+
+// ── Protocol commands ────────────────────────────────────────────────────────
+
+/// `ejs capabilities`
+pub fn capabilities() -> CliResult {
+    println!("{}", serde_json::to_string_pretty(&Capabilities::detect())?);
+    Ok(())
+}
+
+/// `ejs schema [input|output|capabilities]`; without an argument prints
+/// both the input and output schemas
+#[cfg(feature = "schema")]
+pub fn schema(which: Option<&str>) -> CliResult {
+    use ytdlp_ejs::protocol::{capabilities_schema, input_schema, output_schema};
+
+    let schema = match which {
+        None => serde_json::json!({
+            "input": input_schema(),
+            "output": output_schema(),
+        }),
+        Some("input") => serde_json::to_value(input_schema())?,
+        Some("output") => serde_json::to_value(output_schema())?,
+        Some("capabilities") => serde_json::to_value(capabilities_schema())?,
+        Some(other) => {
+            return Err(format!("Unknown schema '{}' (input, output, capabilities)", other).into());
+        }
+    };
+    println!("{}", serde_json::to_string_pretty(&schema)?);
+    Ok(())
+}
+
+#[cfg(not(feature = "schema"))]
+pub fn schema(_which: Option<&str>) -> CliResult {
+    Err("schema requires the 'schema' feature".into())
+}
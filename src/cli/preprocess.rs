@@ -0,0 +1,51 @@
+//! `ejs preprocess`: write the preprocessed player for later use with
+//! `ejs solve --preprocessed`
+
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, unknown_flag};
+use std::fs;
+use ytdlp_ejs::trace::info;
+use ytdlp_ejs::{SolverConfig, preprocess_player_with};
+
+pub fn usage(program: &str) {
+    eprintln!("Usage: {} preprocess [OPTIONS] <player>", program);
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  -o, --output <file>  Write to <file> instead of stdout");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --help, -h           Show this help message");
+}
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    let mut player_path = None;
+    let mut output_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output_path = Some(args.value(&arg)?),
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ if player_path.is_none() => player_path = Some(arg),
+            _ => return Err(format!("Unexpected argument '{}'", arg).into()),
+        }
+    }
+    let player_path = player_path.ok_or("Missing player file argument")?;
+    let player = fs::read_to_string(&player_path)?;
+
+    let code = preprocess_player_with(&player, &config)?;
+    info!(
+        input_len = player.len(),
+        output_len = code.len(),
+        "Preprocessed player"
+    );
+    match output_path {
+        Some(path) => fs::write(path, code)?,
+        None => print!("{}", code),
+    }
+    Ok(())
+}
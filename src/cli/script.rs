@@ -0,0 +1,190 @@
+//! `ejs --script <file>`: bare JS interpreter mode, used by yt-dlp as a
+//! QuickJS replacement
+//!
+//! The common `qjs` flags are accepted too (`-e`, `-m`, `--std`, script
+//! arguments, `-` for stdin); flags ejs does not implement are rejected.
+//!
+//! yt-dlp invokes: ejs --script <temp_file.js>
+//! The temp file contains lib.js + core.js + console.log(JSON.stringify(jsc(...)))
+//! We execute it via embedded QuickJS and output the console.log result.
+//!
+//! IMPORTANT: yt-dlp checks `if proc.returncode or stderr` — any stderr
+//! output causes the request to fail. We must NOT write to stderr in the
+//! success path. All diagnostic logging goes through the tracing subscriber
+//! (controlled by RUST_LOG), which writes to stderr only when enabled.
+
+use super::{Args, CliResult};
+use ytdlp_ejs::SolverConfig;
+use ytdlp_ejs::registry::is_challenge_arg;
+
+#[cfg(feature = "qjs")]
+use std::{fs, process};
+#[cfg(feature = "qjs")]
+use ytdlp_ejs::protocol::extract_jsc_input;
+#[cfg(feature = "qjs")]
+use ytdlp_ejs::trace::info;
+#[cfg(feature = "qjs")]
+use ytdlp_ejs::{RuntimeType, ScriptOptions};
+
+/// First arguments that always select script mode instead of `solve`
+pub const SCRIPT_FLAGS: &[&str] = &[
+    "--script",
+    "-e",
+    "--eval",
+    "-m",
+    "--module",
+    "--std",
+    "-q",
+    "--quit",
+    "--stack-size",
+    "-",
+];
+
+/// Whether `args` (without the program name) are a `qjs` command line
+/// rather than `solve`
+///
+/// `ejs script.js` runs the script, but `ejs player.js n:...` (or any other
+/// registered `<type>:`) is still the original positional form of `solve`.
+pub fn selects(args: &[String]) -> bool {
+    match args.first().map(String::as_str) {
+        // Also a `solve` option, so what follows its value decides
+        Some("--memory-limit") => selects(args.get(2..).unwrap_or_default()),
+        Some(flag) if SCRIPT_FLAGS.contains(&flag) || UNSUPPORTED.contains(&flag) => true,
+        Some(file) if file.ends_with(".js") || file.ends_with(".mjs") => {
+            !args[1..].iter().any(|arg| is_challenge_arg(arg))
+        }
+        _ => false,
+    }
+}
+
+/// `qjs` options that are recognised but not implemented
+const UNSUPPORTED: &[&str] = &[
+    "-i",
+    "--interactive",
+    "-I",
+    "--include",
+    "-c",
+    "--compile",
+    "-o",
+    "--out",
+    "-T",
+    "--trace",
+    "-d",
+    "--dump",
+    "-D",
+    "--dump-flags",
+    "--bignum",
+    "--qjscalc",
+    "--unhandled-rejection",
+];
+
+/// What to evaluate, from a `qjs`-style (or emulated runtime) command line
+#[cfg_attr(not(feature = "qjs"), allow(dead_code))]
+#[derive(Debug, Default)]
+pub struct Invocation {
+    /// `-e <expr>`; takes precedence over the script file, as in qjs
+    pub expr: Option<String>,
+    /// Script path, `-` for stdin
+    pub file: Option<String>,
+    /// Arguments after the script path
+    pub args: Vec<String>,
+    pub module: bool,
+    pub std: bool,
+    pub memory_limit: Option<usize>,
+    pub stack_size: Option<usize>,
+}
+
+/// Parse `qjs [options] [file [args]]`; option parsing stops at the script
+/// path, like qjs
+fn parse(mut args: Args) -> Result<Invocation, String> {
+    let mut inv = Invocation::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--script" => inv.file = Some(args.value(&arg)?),
+            "-e" | "--eval" => inv.expr = Some(args.value(&arg)?),
+            "-m" | "--module" => inv.module = true,
+            "--std" => inv.std = true,
+            // Scripts never enter the REPL, so there is nothing to quit
+            "-q" | "--quit" => {}
+            "--memory-limit" => inv.memory_limit = Some(args.parse(&arg)?),
+            "--stack-size" => inv.stack_size = Some(args.parse(&arg)?),
+            "--" => inv.file = args.next(),
+            flag if UNSUPPORTED.contains(&flag) => {
+                return Err(format!("Unsupported qjs option '{}'", flag));
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                return Err(format!("Unknown qjs option '{}'", flag));
+            }
+            _ => inv.file = Some(arg),
+        }
+        if inv.file.is_some() {
+            break;
+        }
+    }
+    inv.args.extend(args);
+    if inv.expr.is_none() && inv.file.is_none() {
+        return Err("No script given: interactive mode is not supported".into());
+    }
+    Ok(inv)
+}
+
+pub fn run(args: Args, config: SolverConfig) -> CliResult {
+    execute(parse(args)?, config)
+}
+
+/// Evaluate `inv`, answering yt-dlp's jsc() scripts natively
+#[cfg(feature = "qjs")]
+pub fn execute(inv: Invocation, config: SolverConfig) -> CliResult {
+    let script_args: Vec<String> = inv.file.iter().chain(&inv.args).cloned().collect();
+    let options = ScriptOptions {
+        filename: inv.file.clone(),
+        module: inv.module || inv.file.as_deref().is_some_and(|f| f.ends_with(".mjs")),
+        std: inv.std,
+        args: script_args,
+        memory_limit: inv.memory_limit,
+        stack_size: inv.stack_size,
+    };
+
+    let code = match (&inv.expr, inv.file.as_deref()) {
+        (Some(expr), _) => expr.clone(),
+        (None, Some("-")) => std::io::read_to_string(std::io::stdin())?,
+        (None, Some(path)) => fs::read_to_string(path)?,
+        (None, None) => return Err("No script given: interactive mode is not supported".into()),
+    };
+    info!(script_len = code.len(), file = ?inv.file, "Executing script");
+
+    // ── Optimized path: extract jsc() JSON input, solve with Rust ─────
+    // yt-dlp's script is: lib.js + core.js + console.log(JSON.stringify(jsc({...})))
+    // Instead of running the entire JS program (which uses meriyah for
+    // preprocessing), we extract the JSON argument from the jsc() call
+    // and feed it directly into our native Rust preprocessing pipeline.
+    if let Some(input) = extract_jsc_input(&code).filter(|_| !options.module) {
+        info!("Extracted jsc input from script — using native Rust solver");
+        let config = match config.runtime {
+            Some(_) => config,
+            None => config.with_runtime(RuntimeType::QuickJS),
+        };
+        let output = ytdlp_ejs::process_input(input, config);
+        let json = serde_json::to_string(&output)?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    // Fallback: run the full script via embedded QuickJS
+    info!("Could not extract jsc input, falling back to QuickJS interpreter");
+    match ytdlp_ejs::run_script_with(&code, &options) {
+        Ok(output) => {
+            print!("{}", output);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "qjs"))]
+pub fn execute(_inv: Invocation, _config: SolverConfig) -> CliResult {
+    Err("Script mode requires the 'qjs' feature".into())
+}
@@ -0,0 +1,56 @@
+//! `ejs serve`: read one JSON `JsChallengeInput` per line from stdin and
+//! write one JSON `JsChallengeOutput` per line to stdout
+//!
+//! Keeps a single process alive for many players, so callers only pay the
+//! start-up cost once. Combine with `--cache-dir` to skip preprocessing of
+//! players seen before.
+
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, unknown_flag};
+use std::io::{BufRead, Write};
+use ytdlp_ejs::trace::debug;
+use ytdlp_ejs::{JsChallengeError, JsChallengeInput, JsChallengeOutput, SolverConfig};
+
+pub fn usage(program: &str) {
+    eprintln!("Usage: {} serve [OPTIONS]", program);
+    eprintln!();
+    eprintln!("Reads one JSON input per line from stdin and writes one JSON");
+    eprintln!("output per line to stdout until stdin is closed.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --help, -h           Show this help message");
+}
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+        }
+    }
+
+    let stdin = std::io::stdin().lock();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        debug!(len = line.len(), "Received input");
+        let output = match serde_json::from_str::<JsChallengeInput>(&line) {
+            Ok(input) => ytdlp_ejs::process_input(input, config.clone()),
+            Err(e) => JsChallengeOutput::from_error(&JsChallengeError::InvalidInput(e.to_string())),
+        };
+        serde_json::to_writer(&mut stdout, &output)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
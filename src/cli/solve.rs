@@ -0,0 +1,159 @@
+//! `ejs solve`: solve `<type>:<challenge>` pairs against a player file, or
+//! against the output of `ejs preprocess` with `--preprocessed`, or answer
+//! a complete JSON `JsChallengeInput` with `--input`
+//!
+//! This is also the default command, so it accepts the top-level
+//! `--help`, `--version` and `--capabilities` flags when invoked without
+//! a command name.
+
+use super::format::{self, EXIT_CHALLENGE_FAILED, FORMAT_OPTION, Format};
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, unknown_flag};
+use std::fs;
+use std::io::Write;
+use ytdlp_ejs::trace::{debug, info};
+use ytdlp_ejs::{JsChallengeInput, PROTOCOL_VERSION, SolverConfig};
+
+pub fn usage(program: &str) {
+    eprintln!(
+        "Usage: {} solve [OPTIONS] <player> <type>:<challenge> ...",
+        program
+    );
+    eprintln!(
+        "       {} solve [OPTIONS] --preprocessed <file> <type>:<challenge> ...",
+        program
+    );
+    eprintln!("       {} solve [OPTIONS] --input <file|->", program);
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --preprocessed <file> Solve against code written by 'preprocess'");
+    eprintln!("  --input <file|->     Read a JSON JsChallengeInput from <file> or stdin");
+    eprintln!("  --output-preprocessed  Include the preprocessed player in the output");
+    eprintln!("{}", FORMAT_OPTION);
+    eprintln!("  --help, -h           Show this help message");
+    eprintln!();
+    eprintln!(
+        "Exits with {} when the output was printed but a challenge failed.",
+        EXIT_CHALLENGE_FAILED
+    );
+}
+
+enum Source {
+    Player(String),
+    Preprocessed(String),
+}
+
+/// Read `path`, or stdin for `-`
+fn read_input(path: &str) -> std::io::Result<String> {
+    if path == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Run `solve`; `implicit` is set when no command name was given
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig, implicit: bool) -> CliResult {
+    let mut preprocessed_path: Option<String> = None;
+    let mut input_path: Option<String> = None;
+    let mut format = Format::default();
+    let mut positional = vec![];
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--preprocessed" => preprocessed_path = Some(args.value(&arg)?),
+            "--input" => input_path = Some(args.value(&arg)?),
+            "--output-preprocessed" => config.output_preprocessed = true,
+            "--format" => {
+                let name = args.value(&arg)?;
+                format = Format::parse(&name).ok_or_else(|| {
+                    format!("Unknown format '{}' (json, pretty, lines, tsv)", name)
+                })?;
+            }
+            "--help" | "-h" if implicit => {
+                super::print_usage(program);
+                return Ok(());
+            }
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            "--version" | "-V" if implicit => {
+                println!("ejs {}", env!("CARGO_PKG_VERSION"));
+                return Ok(());
+            }
+            "--capabilities" if implicit => return super::capabilities(),
+            flag if flag.starts_with('-') && flag != "-" => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let input = match input_path {
+        Some(path) => {
+            if preprocessed_path.is_some() || !positional.is_empty() {
+                return Err("--input cannot be combined with a player or challenges".into());
+            }
+            let json = read_input(&path)?;
+            debug!(path = %path, runtime = ?config.runtime, "Processing JSON input");
+            serde_json::from_str(&json).map_err(|e| format!("Invalid input {}: {}", path, e))?
+        }
+        None => {
+            // Without `--preprocessed`, the first positional argument is the player
+            let (source, requests_args) = match preprocessed_path {
+                Some(path) => (Source::Preprocessed(path), positional),
+                None if positional.is_empty() => {
+                    return Err("Missing player file argument".into());
+                }
+                None => {
+                    let requests_args = positional.split_off(1);
+                    (Source::Player(positional.remove(0)), requests_args)
+                }
+            };
+            if requests_args.is_empty() {
+                return Err("At least one request is required".into());
+            }
+
+            debug!(?requests_args, runtime = ?config.runtime, "Processing requests");
+            let requests = ytdlp_ejs::parse_challenges(&requests_args)?;
+            match source {
+                Source::Player(path) => {
+                    debug!(path = %path, "Loading player file");
+                    let player = fs::read_to_string(&path)?;
+                    info!(size = player.len(), path = %path, "Loaded player file");
+                    JsChallengeInput::Player {
+                        protocol_version: Some(PROTOCOL_VERSION),
+                        player,
+                        requests,
+                        output_preprocessed: false,
+                    }
+                }
+                Source::Preprocessed(path) => {
+                    let preprocessed_player = fs::read_to_string(&path)?;
+                    info!(size = preprocessed_player.len(), path = %path, "Loaded preprocessed player");
+                    JsChallengeInput::Preprocessed {
+                        protocol_version: Some(PROTOCOL_VERSION),
+                        preprocessed_player,
+                        requests,
+                    }
+                }
+            }
+        }
+    };
+
+    let (JsChallengeInput::Player { requests, .. }
+    | JsChallengeInput::Preprocessed { requests, .. }) = &input;
+    let requests = requests.clone();
+    let output = ytdlp_ejs::process_input(input, config);
+    println!("{}", format::render(format, &requests, &output)?);
+
+    info!("Done");
+    if format::any_failed(&requests, &output) {
+        std::io::stdout().flush()?;
+        std::process::exit(EXIT_CHALLENGE_FAILED);
+    }
+    Ok(())
+}
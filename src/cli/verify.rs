@@ -0,0 +1,385 @@
+//! `ejs verify`: solve the same challenges with every available runtime and
+//! report where they disagree
+//!
+//! The player is preprocessed once and the result handed to each runtime,
+//! so differences come from the engines rather than from preprocessing.
+
+use super::format::{EXIT_CHALLENGE_FAILED, kind_name};
+use super::{Args, CONFIG_OPTIONS, CliResult, config_flag, parse_runtime, unknown_flag};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ytdlp_ejs::trace::info;
+use ytdlp_ejs::{
+    JsChallengeFailure, JsChallengeRequest, JsChallengeResponse, JsChallengeType, RuntimeType,
+    Solver, SolverConfig, parse_challenges, preprocess_player_with,
+};
+
+/// Random challenges per type when none are given
+const DEFAULT_RANDOM: usize = 3;
+
+/// Cells wider than this are shortened in the text matrix
+const CELL_WIDTH: usize = 24;
+
+pub fn usage(program: &str) {
+    eprintln!(
+        "Usage: {} verify [OPTIONS] <player> [<type>:<challenge> ...]",
+        program
+    );
+    eprintln!();
+    eprintln!("Solves with every available runtime and prints a matrix of the results.");
+    eprintln!("Without challenges, random n and sig challenges are generated.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("{}", CONFIG_OPTIONS);
+    eprintln!("  --compare <runtime>  Compare only these runtimes (repeatable)");
+    eprintln!(
+        "  --random <n>         Random challenges per type (default {})",
+        DEFAULT_RANDOM
+    );
+    eprintln!("  --random-seed <n>    Seed for random challenges (default: the clock)");
+    eprintln!("  --json               Print the report as JSON");
+    eprintln!("  --help, -h           Show this help message");
+    eprintln!();
+    eprintln!(
+        "Exits with {} when runtimes disagree or any runtime fails.",
+        EXIT_CHALLENGE_FAILED
+    );
+}
+
+#[derive(Serialize)]
+struct Report {
+    player: String,
+    runtimes: Vec<RuntimeReport>,
+    rows: Vec<Row>,
+    disagreements: usize,
+    errors: usize,
+}
+
+#[derive(Serialize)]
+struct RuntimeReport {
+    name: &'static str,
+    available: bool,
+    /// Why the runtime could not be started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsChallengeFailure>,
+}
+
+#[derive(Serialize)]
+struct Row {
+    #[serde(rename = "type")]
+    challenge_type: String,
+    challenge: String,
+    status: Status,
+    /// Outcome per runtime, for runtimes that were started
+    results: BTreeMap<&'static str, Cell>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    /// Every runtime returned the same result
+    Agree,
+    /// Runtimes returned different results
+    Disagree,
+    /// The results agree, but some runtime failed
+    Error,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+enum Cell {
+    Result(String),
+    Error(JsChallengeFailure),
+}
+
+pub fn run(program: &str, mut args: Args, mut config: SolverConfig) -> CliResult {
+    let mut runtimes = Vec::new();
+    let mut random = None;
+    let mut seed = None;
+    let mut json = false;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compare" => runtimes.push(parse_runtime(&args.value(&arg)?)?),
+            "--random" => random = Some(args.parse::<usize>(&arg)?),
+            "--random-seed" => seed = Some(args.parse::<u64>(&arg)?),
+            "--json" => json = true,
+            "--help" | "-h" => {
+                usage(program);
+                return Ok(());
+            }
+            flag if flag.starts_with('-') && flag != "-" => {
+                if !config_flag(flag, &mut args, &mut config)? {
+                    return Err(unknown_flag(flag));
+                }
+            }
+            _ => positional.push(arg),
+        }
+    }
+    if positional.is_empty() {
+        return Err("Missing player file argument".into());
+    }
+    let challenges = positional.split_off(1);
+    let player_path = positional.remove(0);
+
+    if runtimes.is_empty() {
+        runtimes = RuntimeType::available_runtimes()
+            .into_iter()
+            .filter_map(RuntimeType::parse)
+            .collect();
+    }
+    let requests = match (challenges.is_empty(), random) {
+        (false, None) => parse_challenges(&challenges)?,
+        (false, Some(_)) => return Err("--random cannot be combined with challenges".into()),
+        (true, count) => {
+            let seed = seed.unwrap_or_else(clock_seed);
+            info!(seed, "Generating random challenges");
+            random_requests(count.unwrap_or(DEFAULT_RANDOM), seed)
+        }
+    };
+
+    let player = fs::read_to_string(&player_path)?;
+    let preprocessed = preprocess_player_with(&player, &config)?;
+    let report = verify(player_path, &preprocessed, &config, &runtimes, &requests);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_matrix(&report);
+    }
+    if report.disagreements > 0 || report.errors > 0 {
+        std::io::stdout().flush()?;
+        std::process::exit(EXIT_CHALLENGE_FAILED);
+    }
+    Ok(())
+}
+
+fn verify(
+    player: String,
+    preprocessed: &str,
+    config: &SolverConfig,
+    runtimes: &[RuntimeType],
+    requests: &[JsChallengeRequest],
+) -> Report {
+    let mut runtime_reports = Vec::new();
+    // (runtime, one response per request)
+    let mut columns: Vec<(&'static str, Vec<JsChallengeResponse>)> = Vec::new();
+    for runtime in runtimes {
+        let name = runtime.name();
+        if !runtime.is_available() {
+            runtime_reports.push(RuntimeReport {
+                name,
+                available: false,
+                error: None,
+            });
+            continue;
+        }
+        let mut config = config.clone().with_runtime(*runtime);
+        config.fallbacks.clear();
+        info!(runtime = name, "Verifying runtime");
+        match Solver::from_preprocessed(preprocessed.to_string(), config) {
+            Ok(mut solver) => {
+                let responses = requests.iter().map(|r| solver.solve(r)).collect();
+                columns.push((name, responses));
+                runtime_reports.push(RuntimeReport {
+                    name,
+                    available: true,
+                    error: None,
+                });
+            }
+            Err(e) => runtime_reports.push(RuntimeReport {
+                name,
+                available: true,
+                error: Some(JsChallengeFailure::from(&e)),
+            }),
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (i, request) in requests.iter().enumerate() {
+        for challenge in &request.challenges {
+            let results: BTreeMap<_, _> = columns
+                .iter()
+                .map(|(name, responses)| (*name, cell(&responses[i], challenge)))
+                .collect();
+            rows.push(Row {
+                challenge_type: request.challenge_type.as_str().to_string(),
+                challenge: challenge.clone(),
+                status: status(results.values()),
+                results,
+            });
+        }
+    }
+
+    let count = |status: Status| rows.iter().filter(|row| row.status == status).count();
+    let failed_runtimes = runtime_reports.iter().filter(|r| r.error.is_some()).count();
+    Report {
+        player,
+        disagreements: count(Status::Disagree),
+        errors: count(Status::Error) + failed_runtimes,
+        runtimes: runtime_reports,
+        rows,
+    }
+}
+
+fn cell(response: &JsChallengeResponse, challenge: &str) -> Cell {
+    match response {
+        JsChallengeResponse::Result { data, errors, .. } => {
+            match data.get(challenge) {
+                Some(result) => Cell::Result(result.clone()),
+                None => Cell::Error(errors.get(challenge).cloned().unwrap_or_else(|| {
+                    JsChallengeFailure {
+                        kind: Default::default(),
+                        message: "No result returned".into(),
+                        details: None,
+                    }
+                })),
+            }
+        }
+        JsChallengeResponse::Error {
+            error,
+            kind,
+            details,
+        } => Cell::Error(JsChallengeFailure {
+            kind: *kind,
+            message: error.clone(),
+            details: details.clone(),
+        }),
+    }
+}
+
+fn status<'a>(cells: impl Iterator<Item = &'a Cell>) -> Status {
+    let mut first = None;
+    let mut failed = false;
+    for cell in cells {
+        match cell {
+            Cell::Result(result) => match first {
+                None => first = Some(result),
+                Some(first) if first != result => return Status::Disagree,
+                Some(_) => {}
+            },
+            Cell::Error(_) => failed = true,
+        }
+    }
+    if failed { Status::Error } else { Status::Agree }
+}
+
+fn print_matrix(report: &Report) {
+    let names: Vec<&str> = report
+        .runtimes
+        .iter()
+        .filter(|r| r.available && r.error.is_none())
+        .map(|r| r.name)
+        .collect();
+
+    let mut table = vec![
+        ["status", "type", "challenge"]
+            .into_iter()
+            .map(String::from)
+            .chain(names.iter().map(|name| name.to_string()))
+            .collect::<Vec<_>>(),
+    ];
+    for row in &report.rows {
+        let status = match row.status {
+            Status::Agree => "ok",
+            Status::Disagree => "DIFF",
+            Status::Error => "ERR",
+        };
+        let mut line = vec![
+            status.to_string(),
+            row.challenge_type.clone(),
+            shorten(&row.challenge),
+        ];
+        for name in &names {
+            line.push(match &row.results[name] {
+                Cell::Result(result) => shorten(result),
+                Cell::Error(failure) => format!("!{}", kind_name(failure.kind)),
+            });
+        }
+        table.push(line);
+    }
+
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|col| {
+            table
+                .iter()
+                .map(|line| line[col].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    for line in &table {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", cells.join("  ").trim_end());
+    }
+
+    println!();
+    for runtime in &report.runtimes {
+        match (&runtime.error, runtime.available) {
+            (Some(failure), _) => {
+                println!("{}: failed to start: {}", runtime.name, failure.message)
+            }
+            (None, false) => println!("{}: skipped (not available)", runtime.name),
+            (None, true) => {}
+        }
+    }
+    println!(
+        "{} challenges, {} runtimes compared, {} disagreements, {} errors",
+        report.rows.len(),
+        names.len(),
+        report.disagreements,
+        report.errors
+    );
+}
+
+fn shorten(text: &str) -> String {
+    if text.chars().count() <= CELL_WIDTH {
+        return text.to_string();
+    }
+    let head: String = text.chars().take(CELL_WIDTH - 3).collect();
+    format!("{}...", head)
+}
+
+// ── Random challenges ────────────────────────────────────────────────────────
+
+const BASE64URL: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+pub fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+}
+
+/// `count` n and sig challenges shaped like the ones YouTube serves
+pub fn random_requests(count: usize, seed: u64) -> Vec<JsChallengeRequest> {
+    let mut state = seed | 1;
+    let mut next = move || {
+        // xorshift64*
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    };
+    let mut challenge = |len: usize| -> String {
+        (0..len)
+            .map(|_| BASE64URL[(next() % BASE64URL.len() as u64) as usize] as char)
+            .collect()
+    };
+    vec![
+        JsChallengeRequest {
+            challenge_type: JsChallengeType::N,
+            challenges: (0..count).map(|_| challenge(16)).collect(),
+        },
+        JsChallengeRequest {
+            challenge_type: JsChallengeType::Sig,
+            challenges: (0..count).map(|_| challenge(104)).collect(),
+        },
+    ]
+}
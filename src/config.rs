@@ -0,0 +1,392 @@
+//! Solver Configuration
+//!
+//! [`SolverConfig`] gathers every tunable of the pipeline: runtime choice and
+//! fallbacks, limits, caching and preprocessing output. It is built in code
+//! with the `with_*` methods, or loaded from a JSON file and `EJS_*`
+//! environment variables, so the library and the CLI share one format.
+
+use crate::provider::JsChallengeError;
+use crate::registry::{Runtime, RuntimeType};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default QuickJS stack limit (16 MB)
+pub const DEFAULT_STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Default stack of the thread running preprocessing in the CLI (32 MB)
+pub const DEFAULT_THREAD_STACK_SIZE: usize = 32 * 1024 * 1024;
+
+/// Browser shims injected ahead of the player code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolyfillProfile {
+    /// `setup.js` and the `Intl` polyfill
+    #[default]
+    Full,
+    /// `setup.js` only, for engines with a native `Intl`
+    Minimal,
+    /// No polyfills
+    None,
+}
+
+impl PolyfillProfile {
+    /// Parse profile from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "full" => Some(Self::Full),
+            "minimal" => Some(Self::Minimal),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for deterministic execution
+///
+/// The polyfill layer seeds `Math.random`, freezes `Date` at `now_ms`, pins
+/// `performance.now()` to zero and turns timers into no-ops, identically in
+/// every runtime. Requires a polyfill profile other than
+/// [`PolyfillProfile::None`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Deterministic {
+    /// Seed of the `Math.random` generator
+    pub seed: u32,
+    /// Frozen clock, in milliseconds since the Unix epoch
+    pub now_ms: u64,
+}
+
+impl Default for Deterministic {
+    fn default() -> Self {
+        Self {
+            seed: 0x5eed,
+            // 2024-01-01T00:00:00Z
+            now_ms: 1_704_067_200_000,
+        }
+    }
+}
+
+/// How solved values that fail a plausibility check are reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strictness {
+    /// Do not check results
+    Off,
+    /// Keep the result and add a warning to the response
+    #[default]
+    Warn,
+    /// Report the result as an `implausible` error
+    Strict,
+}
+
+impl Strictness {
+    /// Parse strictness from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// Configuration for preprocessing and solving
+///
+/// Limits are applied where the runtime supports them: QuickJS honours the
+/// timeout, memory limit and stack size; Node and Deno the timeout and
+/// memory limit; Bun the timeout only; Boa none of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SolverConfig {
+    /// Primary runtime; defaults to the first built-in engine compiled in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<Runtime>,
+    /// Runtimes tried in order when the previous one is unavailable
+    pub fallbacks: Vec<Runtime>,
+    /// Time limit for evaluating the player and for each challenge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Heap limit in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit: Option<usize>,
+    /// Maximum JS stack size in bytes
+    pub stack_size: usize,
+    /// Stack size in bytes for threads that preprocess players or run
+    /// in-process engines
+    pub thread_stack_size: usize,
+    /// Directory for caching preprocessed players across runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    /// Polyfills injected during preprocessing
+    pub polyfills: PolyfillProfile,
+    /// Extra command-line flags for external runtimes
+    pub runtime_args: Vec<String>,
+    /// Always include the preprocessed player in the output
+    pub output_preprocessed: bool,
+    /// Emit minified preprocessed code
+    pub minify: bool,
+    /// Handling of solved values that look like silent failures
+    pub strictness: Strictness,
+    /// Run the player with a seeded random source and a frozen clock
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deterministic: Option<Deterministic>,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            runtime: None,
+            fallbacks: Vec::new(),
+            timeout_ms: None,
+            memory_limit: None,
+            stack_size: DEFAULT_STACK_SIZE,
+            thread_stack_size: DEFAULT_THREAD_STACK_SIZE,
+            cache_dir: None,
+            polyfills: PolyfillProfile::Full,
+            runtime_args: Vec::new(),
+            output_preprocessed: false,
+            minify: false,
+            strictness: Strictness::Warn,
+            deterministic: None,
+        }
+    }
+}
+
+impl SolverConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_runtime(mut self, runtime: impl Into<Runtime>) -> Self {
+        self.runtime = Some(runtime.into());
+        self
+    }
+
+    pub fn with_fallback(mut self, runtime: impl Into<Runtime>) -> Self {
+        self.fallbacks.push(runtime.into());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    pub fn with_stack_size(mut self, bytes: usize) -> Self {
+        self.stack_size = bytes;
+        self
+    }
+
+    pub fn with_thread_stack_size(mut self, bytes: usize) -> Self {
+        self.thread_stack_size = bytes;
+        self
+    }
+
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_polyfills(mut self, polyfills: PolyfillProfile) -> Self {
+        self.polyfills = polyfills;
+        self
+    }
+
+    pub fn with_runtime_arg(mut self, arg: impl Into<String>) -> Self {
+        self.runtime_args.push(arg.into());
+        self
+    }
+
+    pub fn with_output_preprocessed(mut self, output_preprocessed: bool) -> Self {
+        self.output_preprocessed = output_preprocessed;
+        self
+    }
+
+    pub fn with_minify(mut self, minify: bool) -> Self {
+        self.minify = minify;
+        self
+    }
+
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    pub fn with_deterministic(mut self, deterministic: Deterministic) -> Self {
+        self.deterministic = Some(deterministic);
+        self
+    }
+
+    /// Time limit as a [`Duration`]
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.map(Duration::from_millis)
+    }
+
+    /// The primary runtime followed by the fallbacks
+    pub fn runtimes(&self) -> Result<Vec<Runtime>, JsChallengeError> {
+        let primary = match &self.runtime {
+            Some(runtime) => runtime.clone(),
+            None => RuntimeType::default_runtime()
+                .ok_or_else(|| JsChallengeError::RuntimeUnavailable {
+                    runtime: "default".into(),
+                    message: "no runtime compiled in".into(),
+                })?
+                .into(),
+        };
+        Ok(std::iter::once(primary)
+            .chain(self.fallbacks.iter().cloned())
+            .collect())
+    }
+
+    /// Load a JSON config file; missing fields keep their defaults
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, JsChallengeError> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(|e| {
+            JsChallengeError::InvalidInput(format!("Invalid config {}: {}", path.display(), e))
+        })
+    }
+
+    /// Defaults, overlaid with the file named by `EJS_CONFIG` and then the
+    /// other `EJS_*` variables
+    pub fn from_env() -> Result<Self, JsChallengeError> {
+        let config = match std::env::var_os("EJS_CONFIG") {
+            Some(path) => Self::from_file(path)?,
+            None => Self::default(),
+        };
+        config.with_env()
+    }
+
+    /// Overlay `EJS_*` environment variables onto this config
+    ///
+    /// `EJS_RUNTIME`, `EJS_FALLBACKS` (comma-separated), `EJS_TIMEOUT_MS`,
+    /// `EJS_MEMORY_LIMIT`, `EJS_STACK_SIZE`, `EJS_THREAD_STACK_SIZE`,
+    /// `EJS_CACHE_DIR`, `EJS_POLYFILLS`, `EJS_RUNTIME_ARGS`
+    /// (whitespace-separated), `EJS_OUTPUT_PREPROCESSED`, `EJS_MINIFY`,
+    /// `EJS_STRICTNESS`, and `EJS_DETERMINISTIC`, `EJS_SEED` and
+    /// `EJS_CLOCK_MS`, either of the latter two implying the first.
+    pub fn with_env(mut self) -> Result<Self, JsChallengeError> {
+        if let Some(value) = env_var("EJS_RUNTIME") {
+            self.runtime = Some(parse_runtime("EJS_RUNTIME", &value)?);
+        }
+        if let Some(value) = env_var("EJS_FALLBACKS") {
+            self.fallbacks = value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_runtime("EJS_FALLBACKS", s))
+                .collect::<Result<_, _>>()?;
+        }
+        if let Some(value) = env_var("EJS_TIMEOUT_MS") {
+            self.timeout_ms = Some(parse_number("EJS_TIMEOUT_MS", &value)?);
+        }
+        if let Some(value) = env_var("EJS_MEMORY_LIMIT") {
+            self.memory_limit = Some(parse_number("EJS_MEMORY_LIMIT", &value)?);
+        }
+        if let Some(value) = env_var("EJS_STACK_SIZE") {
+            self.stack_size = parse_number("EJS_STACK_SIZE", &value)?;
+        }
+        if let Some(value) = env_var("EJS_THREAD_STACK_SIZE") {
+            self.thread_stack_size = parse_number("EJS_THREAD_STACK_SIZE", &value)?;
+        }
+        if let Some(value) = env_var("EJS_CACHE_DIR") {
+            self.cache_dir = Some(value.into());
+        }
+        if let Some(value) = env_var("EJS_POLYFILLS") {
+            self.polyfills = PolyfillProfile::parse(&value).ok_or_else(|| {
+                JsChallengeError::InvalidInput(format!(
+                    "EJS_POLYFILLS: unknown profile '{}' (full, minimal, none)",
+                    value
+                ))
+            })?;
+        }
+        if let Some(value) = env_var("EJS_RUNTIME_ARGS") {
+            self.runtime_args = value.split_whitespace().map(String::from).collect();
+        }
+        if let Some(value) = env_var("EJS_OUTPUT_PREPROCESSED") {
+            self.output_preprocessed = parse_bool("EJS_OUTPUT_PREPROCESSED", &value)?;
+        }
+        if let Some(value) = env_var("EJS_MINIFY") {
+            self.minify = parse_bool("EJS_MINIFY", &value)?;
+        }
+        if let Some(value) = env_var("EJS_STRICTNESS") {
+            self.strictness = Strictness::parse(&value).ok_or_else(|| {
+                JsChallengeError::InvalidInput(format!(
+                    "EJS_STRICTNESS: unknown value '{}' (off, warn, strict)",
+                    value
+                ))
+            })?;
+        }
+        if let Some(value) = env_var("EJS_DETERMINISTIC") {
+            self.deterministic = parse_bool("EJS_DETERMINISTIC", &value)?
+                .then(|| self.deterministic.unwrap_or_default());
+        }
+        if let Some(value) = env_var("EJS_SEED") {
+            self.deterministic.get_or_insert_default().seed = parse_number("EJS_SEED", &value)?;
+        }
+        if let Some(value) = env_var("EJS_CLOCK_MS") {
+            self.deterministic.get_or_insert_default().now_ms =
+                parse_number("EJS_CLOCK_MS", &value)?;
+        }
+        Ok(self)
+    }
+}
+
+impl From<Runtime> for SolverConfig {
+    fn from(runtime: Runtime) -> Self {
+        Self::default().with_runtime(runtime)
+    }
+}
+
+impl From<RuntimeType> for SolverConfig {
+    fn from(runtime_type: RuntimeType) -> Self {
+        Self::default().with_runtime(runtime_type)
+    }
+}
+
+// ── Environment parsing ──────────────────────────────────────────────────────
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+fn parse_runtime(name: &str, value: &str) -> Result<Runtime, JsChallengeError> {
+    RuntimeType::parse(value.trim())
+        .map(Runtime::from)
+        .ok_or_else(|| {
+            JsChallengeError::InvalidInput(format!(
+                "{}: unknown runtime '{}'. Available: {}",
+                name,
+                value,
+                RuntimeType::available_runtimes().join(", ")
+            ))
+        })
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, JsChallengeError>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|e| JsChallengeError::InvalidInput(format!("{}: {}", name, e)))
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, JsChallengeError> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(JsChallengeError::InvalidInput(format!(
+            "{}: expected a boolean, got '{}'",
+            name, value
+        ))),
+    }
+}
@@ -1,95 +1,137 @@
 //! JS Challenge Request Director
 
-use crate::builtin::JsRuntimeProvider;
-use crate::builtin::preprocessor::preprocess_player;
-use crate::provider::{
-    JsChallengeError, JsChallengeInput, JsChallengeOutput, JsChallengeRequest, JsChallengeResponse,
-};
-use crate::registry::RuntimeType;
-use crate::trace::{debug, error, info, trace_span};
+use crate::config::SolverConfig;
+use crate::protocol::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use crate::provider::{JsChallengeError, JsChallengeInput, JsChallengeOutput};
+use crate::solver::Solver;
+use crate::trace::{error, trace_span};
 
 /// Process input with specified runtime and return output
-pub fn process_input(input: JsChallengeInput, runtime_type: RuntimeType) -> JsChallengeOutput {
-    match process_internal(input, runtime_type) {
+///
+/// `config` is a [`SolverConfig`], or just the runtime to use: a built-in
+/// [`RuntimeType`](crate::RuntimeType) or a
+/// [`Runtime::Custom`](crate::Runtime::Custom) implementation.
+pub fn process_input(
+    input: JsChallengeInput,
+    config: impl Into<SolverConfig>,
+) -> JsChallengeOutput {
+    match process_internal(input, config.into()) {
         Ok(output) => output,
         Err(e) => {
             error!(%e, "Processing failed");
-            JsChallengeOutput::Error {
-                error: e.to_string(),
-            }
+            JsChallengeOutput::from_error(&e)
         }
     }
 }
 
+/// Reject inputs written for a protocol this build does not understand
+fn check_protocol_version(input: &JsChallengeInput) -> Result<(), JsChallengeError> {
+    let version = input.protocol_version();
+    if (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(JsChallengeError::InvalidInput(format!(
+            "Unsupported protocol version {} (supported: {}-{})",
+            version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+        )))
+    }
+}
+
 fn process_internal(
     input: JsChallengeInput,
-    runtime_type: RuntimeType,
+    config: SolverConfig,
 ) -> Result<JsChallengeOutput, JsChallengeError> {
-    trace_span!("process_internal", ?runtime_type);
+    trace_span!("process_internal", runtime = ?config.runtime);
+    check_protocol_version(&input)?;
+    let always_output = config.output_preprocessed;
 
-    let (preprocessed, should_output, requests) = match &input {
+    let (mut solver, should_output, requests) = match input {
         JsChallengeInput::Player {
             player,
             output_preprocessed,
             requests,
-        } => {
-            info!(player_len = player.len(), "Preprocessing player code");
-            let code = preprocess_player(player)?;
-            debug!(preprocessed_len = code.len(), "Preprocessing complete");
-            (code, *output_preprocessed, requests)
-        }
+            ..
+        } => (
+            Solver::new(&player, config)?,
+            output_preprocessed || always_output,
+            requests,
+        ),
         JsChallengeInput::Preprocessed {
             preprocessed_player,
             requests,
-        } => {
-            debug!(
-                preprocessed_len = preprocessed_player.len(),
-                "Using preprocessed player code"
-            );
-            (preprocessed_player.clone(), false, requests)
-        }
+            ..
+        } => (
+            Solver::from_preprocessed(preprocessed_player, config)?,
+            false,
+            requests,
+        ),
     };
 
-    debug!(?runtime_type, ?requests, "Creating JS runtime provider");
-    let mut provider = runtime_type.create_provider(&preprocessed)?;
-    info!(?runtime_type, "Runtime provider ready");
-
-    let responses: Vec<JsChallengeResponse> = requests
-        .iter()
-        .map(|req| process_request(&mut provider, req))
-        .collect();
+    let responses = requests.iter().map(|req| solver.solve(req)).collect();
 
-    Ok(JsChallengeOutput::Result {
-        preprocessed_player: if should_output {
-            Some(preprocessed)
-        } else {
-            None
-        },
+    Ok(JsChallengeOutput::result(
+        should_output.then(|| solver.preprocessed_player().to_string()),
         responses,
-    })
+    ))
 }
 
-fn process_request(
-    provider: &mut JsRuntimeProvider,
-    request: &JsChallengeRequest,
-) -> JsChallengeResponse {
-    trace_span!(
-        "process_request",
-        req_type = %request.challenge_type.as_str(),
-        count = request.challenges.len()
-    );
-
-    debug!(?request.challenges, "Solving challenges");
-    match provider.solve_challenges(&request.challenge_type, &request.challenges) {
-        Ok(data) => {
-            info!(results = data.len(), ?data, "Challenges solved");
-            JsChallengeResponse::Result { data }
-        }
+/// Process input with specified runtime without blocking the async executor.
+///
+/// External runtimes run through `tokio::process`; in-process engines and
+/// preprocessing run on tokio's blocking pool.
+#[cfg(feature = "tokio")]
+pub async fn process_input_async(
+    input: JsChallengeInput,
+    config: impl Into<SolverConfig>,
+) -> JsChallengeOutput {
+    match process_internal_async(input, config.into()).await {
+        Ok(output) => output,
         Err(e) => {
-            error!(%e, "Challenge solving failed");
-            JsChallengeResponse::Error {
-                error: e.to_string(),
-            }
+            error!(%e, "Processing failed");
+            JsChallengeOutput::from_error(&e)
         }
     }
 }
+
+#[cfg(feature = "tokio")]
+async fn process_internal_async(
+    input: JsChallengeInput,
+    config: SolverConfig,
+) -> Result<JsChallengeOutput, JsChallengeError> {
+    use crate::solver::AsyncSolver;
+
+    check_protocol_version(&input)?;
+    let always_output = config.output_preprocessed;
+
+    let (solver, should_output, requests) = match input {
+        JsChallengeInput::Player {
+            player,
+            output_preprocessed,
+            requests,
+            ..
+        } => (
+            AsyncSolver::new(player, config).await?,
+            output_preprocessed || always_output,
+            requests,
+        ),
+        JsChallengeInput::Preprocessed {
+            preprocessed_player,
+            requests,
+            ..
+        } => (
+            AsyncSolver::from_preprocessed(preprocessed_player, config).await?,
+            false,
+            requests,
+        ),
+    };
+
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in &requests {
+        responses.push(solver.solve(request).await);
+    }
+
+    Ok(JsChallengeOutput::result(
+        should_output.then(|| solver.preprocessed_player().to_string()),
+        responses,
+    ))
+}
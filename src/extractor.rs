@@ -0,0 +1,39 @@
+//! Pluggable Solver Extractors
+//!
+//! Besides the built-in `n` and `sig` solvers, preprocessing can locate
+//! further functions in the player. Each registered [`SolverExtractor`]
+//! contributes a `_result.<name>` entry, and challenges whose type is
+//! `<name>` are routed to it.
+
+pub use swc_ecma_ast as ast;
+
+use crate::builtin::preprocessor::extract_shared;
+use crate::provider::JsChallengeError;
+use ast::{Expr, Stmt};
+
+/// Locates the solver for one additional challenge type
+pub trait SolverExtractor: Send + Sync {
+    /// Challenge type and `_result` entry this extractor provides.
+    /// Must be a JavaScript identifier other than `n` or `sig`.
+    fn name(&self) -> &str;
+
+    /// Candidate solvers found in one statement of the player's main block
+    ///
+    /// Each candidate must evaluate to a function taking the challenge
+    /// string. Candidates from all statements are combined and must agree
+    /// on the result; if none are found, solving this type throws. An error
+    /// does the same without failing `n`, `sig` or other types.
+    fn extract(&self, stmt: &Stmt) -> Result<Vec<Box<Expr>>, JsChallengeError>;
+}
+
+/// Parse a JavaScript expression, e.g. to build a candidate from source text
+pub fn parse_expr(code: &str) -> Result<Box<Expr>, JsChallengeError> {
+    extract_shared::parse_expr(code)
+}
+
+/// Check that `name` is a plain JavaScript identifier
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
@@ -1,74 +1,126 @@
 //! EJS - JavaScript Challenge Solver Library
 
 pub mod builtin;
+mod cache;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod config;
 pub mod director;
+pub mod extractor;
+pub mod plausibility;
+pub mod protocol;
 pub mod provider;
+#[cfg(feature = "python")]
+mod python;
 pub mod registry;
+pub mod runtime;
+pub mod solver;
 pub mod test_data;
 pub mod trace;
 
 // ── Public API re-exports ───────────────────────────────────────────────────
 
-pub use builtin::preprocessor::preprocess_player;
+pub use builtin::preprocessor::{
+    PreprocessReport, PreprocessTimings, preprocess_player, preprocess_player_report,
+    preprocess_player_timed, preprocess_player_with,
+};
+pub use config::{Deterministic, PolyfillProfile, SolverConfig, Strictness};
 pub use director::process_input;
+#[cfg(feature = "tokio")]
+pub use director::process_input_async;
+pub use extractor::SolverExtractor;
+pub use protocol::{Capabilities, PROTOCOL_VERSION};
 pub use provider::{
-    JsChallengeError, JsChallengeInput, JsChallengeOutput, JsChallengeRequest, JsChallengeResponse,
-    JsChallengeType,
+    JsChallengeError, JsChallengeErrorKind, JsChallengeFailure, JsChallengeInput,
+    JsChallengeOutput, JsChallengeRequest, JsChallengeResponse, JsChallengeType,
 };
-pub use registry::RuntimeType;
+pub use registry::{Runtime, RuntimeType, challenge_types, register_extractor};
+pub use runtime::{JsRuntime, JsRuntimeFactory, RuntimeCapabilities};
+#[cfg(feature = "tokio")]
+pub use solver::AsyncSolver;
+pub use solver::Solver;
 
 #[cfg(feature = "qjs")]
-pub use builtin::quickjs::run_script;
+pub use builtin::quickjs::{ScriptOptions, run_script, run_script_with};
 
-/// Run challenge solver with the specified runtime
+/// Run challenge solver with the specified runtime or [`SolverConfig`]
 pub fn run(
     player: String,
-    runtime: RuntimeType,
+    config: impl Into<SolverConfig>,
     challenges: Vec<String>,
 ) -> Result<JsChallengeOutput, JsChallengeError> {
-    trace_span!("run", ?runtime, player_len = player.len());
+    let config = config.into();
+    trace_span!("run", runtime = ?config.runtime, player_len = player.len());
 
-    let mut n_challenges = Vec::new();
-    let mut sig_challenges = Vec::new();
+    let input = JsChallengeInput::Player {
+        protocol_version: Some(PROTOCOL_VERSION),
+        player,
+        requests: parse_challenges(&challenges)?,
+        output_preprocessed: false,
+    };
 
-    for request in &challenges {
-        let parts: Vec<&str> = request.splitn(2, ':').collect();
-        if parts.len() != 2 {
-            return Err(JsChallengeError::Parse(format!(
+    Ok(process_input(input, config))
+}
+
+/// Like [`run`], for code produced by [`preprocess_player`]; skips parsing
+/// the player entirely
+pub fn run_preprocessed(
+    preprocessed_player: String,
+    config: impl Into<SolverConfig>,
+    challenges: Vec<String>,
+) -> Result<JsChallengeOutput, JsChallengeError> {
+    let config = config.into();
+    trace_span!("run_preprocessed", runtime = ?config.runtime, code_len = preprocessed_player.len());
+
+    let input = JsChallengeInput::Preprocessed {
+        protocol_version: Some(PROTOCOL_VERSION),
+        preprocessed_player,
+        requests: parse_challenges(&challenges)?,
+    };
+
+    Ok(process_input(input, config))
+}
+
+/// Group `<type>:<challenge>` strings into requests, as [`run`] does
+pub fn parse_challenges(challenges: &[String]) -> Result<Vec<JsChallengeRequest>, JsChallengeError> {
+    // `n` and `sig` are always requested so the output shape stays stable;
+    // other types follow in order of first appearance
+    let mut requests = vec![
+        JsChallengeRequest {
+            challenge_type: JsChallengeType::N,
+            challenges: Vec::new(),
+        },
+        JsChallengeRequest {
+            challenge_type: JsChallengeType::Sig,
+            challenges: Vec::new(),
+        },
+    ];
+
+    for request in challenges {
+        let Some((challenge_type, challenge)) = request.split_once(':') else {
+            return Err(JsChallengeError::InvalidInput(format!(
                 "Invalid request format: {}",
                 request
             )));
-        }
+        };
 
-        match parts[0] {
-            "n" => n_challenges.push(parts[1].to_string()),
-            "sig" => sig_challenges.push(parts[1].to_string()),
-            t => return Err(JsChallengeError::Parse(format!("Unsupported type: {}", t))),
-        }
+        let challenge_type = JsChallengeType::from(challenge_type);
+        let index = match requests
+            .iter()
+            .position(|r| r.challenge_type == challenge_type)
+        {
+            Some(index) => index,
+            None => {
+                requests.push(JsChallengeRequest {
+                    challenge_type,
+                    challenges: Vec::new(),
+                });
+                requests.len() - 1
+            }
+        };
+        requests[index].challenges.push(challenge.to_string());
     }
 
-    debug!(
-        n_count = n_challenges.len(),
-        sig_count = sig_challenges.len(),
-        ?n_challenges,
-        ?sig_challenges,
-        "Parsed challenges"
-    );
-
-    let input = JsChallengeInput::Player {
-        player,
-        requests: vec![
-            JsChallengeRequest {
-                challenge_type: JsChallengeType::N,
-                challenges: n_challenges,
-            },
-            JsChallengeRequest {
-                challenge_type: JsChallengeType::Sig,
-                challenges: sig_challenges,
-            },
-        ],
-        output_preprocessed: false,
-    };
-
-    Ok(process_input(input, runtime))
+    debug!(?requests, "Parsed challenges");
+    Ok(requests)
 }
@@ -0,0 +1,109 @@
+//! Output Plausibility Checks
+//!
+//! A broken solver often "succeeds": the n function returns its input or an
+//! `enhanced_except_...` marker, and the download is then silently
+//! throttled. Results are checked against such known failure signatures and
+//! against what a real transform can produce. [`Strictness`] decides whether
+//! a suspicious result is reported as a warning or as an error.
+//!
+//! [`Strictness`]: crate::config::Strictness
+
+use crate::provider::JsChallengeType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Why a solved value looks wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Implausibility {
+    /// The result is empty
+    Empty,
+    /// The result is the challenge itself, or ends with it
+    Identity,
+    /// The result matches a string produced by failing solvers
+    FailureSignature,
+    /// The result length is far from what the transform produces
+    Length,
+    /// The result contains characters the transform never produces
+    Charset,
+    /// A `sig` result uses characters not present in the challenge
+    NotDerived,
+}
+
+impl fmt::Display for Implausibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Empty => "result is empty",
+            Self::Identity => "result echoes the challenge",
+            Self::FailureSignature => "result matches a known failure signature",
+            Self::Length => "result length is implausible",
+            Self::Charset => "result contains unexpected characters",
+            Self::NotDerived => "result is not a rearrangement of the challenge",
+        })
+    }
+}
+
+/// Prefix of the marker returned by n functions that caught an exception
+const FAILURE_PREFIX: &str = "enhanced_except_";
+
+/// Whole results of n functions that returned nothing; a real result may
+/// still start with one of these
+const FAILURE_VALUES: &[&str] = &["undefined", "null", "NaN"];
+
+/// Check `result` as the answer to `challenge` of type `challenge_type`
+///
+/// Only `n` and `sig` have known shapes; other types are only checked for
+/// an empty result.
+pub fn check(
+    challenge_type: &JsChallengeType,
+    challenge: &str,
+    result: &str,
+) -> Result<(), Implausibility> {
+    if result.is_empty() {
+        return Err(Implausibility::Empty);
+    }
+    match challenge_type {
+        JsChallengeType::N => check_n(challenge, result),
+        JsChallengeType::Sig => check_sig(challenge, result),
+        JsChallengeType::Custom(_) => Ok(()),
+    }
+}
+
+fn check_n(challenge: &str, result: &str) -> Result<(), Implausibility> {
+    if result.starts_with(FAILURE_PREFIX) || FAILURE_VALUES.contains(&result) {
+        return Err(Implausibility::FailureSignature);
+    }
+    if result.ends_with(challenge) {
+        return Err(Implausibility::Identity);
+    }
+    if !result
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    {
+        return Err(Implausibility::Charset);
+    }
+    if result.len() * 2 < challenge.len() || result.len() > challenge.len() * 2 {
+        return Err(Implausibility::Length);
+    }
+    Ok(())
+}
+
+/// The sig transform only reverses, swaps and drops characters, so the
+/// result must be a sub-multiset of the challenge
+fn check_sig(challenge: &str, result: &str) -> Result<(), Implausibility> {
+    if result == challenge {
+        return Err(Implausibility::Identity);
+    }
+    let mut available: HashMap<char, usize> = HashMap::new();
+    for c in challenge.chars() {
+        *available.entry(c).or_default() += 1;
+    }
+    for c in result.chars() {
+        match available.get_mut(&c) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => return Err(Implausibility::NotDerived),
+        }
+    }
+    Ok(())
+}
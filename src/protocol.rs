@@ -0,0 +1,205 @@
+//! JSON Protocol Versioning
+//!
+//! The types in [`crate::provider`] are the wire format between yt-dlp (or
+//! any other client) and ejs. Every output carries [`PROTOCOL_VERSION`];
+//! [`Capabilities`] tells a client what a given binary supports, and with
+//! the `schema` feature the format is published as JSON Schema.
+
+use crate::provider::{JsChallengeErrorKind, JsChallengeInput};
+use crate::registry::{RuntimeType, challenge_types};
+use crate::trace::debug;
+use serde::{Deserialize, Serialize};
+
+/// Current version of the JSON protocol
+///
+/// Version 2 added `protocol_version`, per-challenge `errors` and
+/// `warnings`, error `kind`/`details` and arbitrary challenge type strings. Version 1 inputs
+/// are still accepted.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest input protocol version still accepted
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// What this build supports, as reported by `ejs --capabilities`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    /// ejs version
+    pub version: String,
+    /// Accepted input `type` values
+    pub input_types: Vec<String>,
+    /// Accepted challenge types, including registered extractors
+    pub challenge_types: Vec<String>,
+    pub runtimes: Vec<RuntimeInfo>,
+    pub error_kinds: Vec<JsChallengeErrorKind>,
+    /// Cargo features compiled in
+    pub features: Vec<String>,
+}
+
+/// A runtime compiled into this build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RuntimeInfo {
+    pub name: String,
+    /// Embedded in the binary rather than spawned
+    pub in_process: bool,
+    /// Whether it could be started right now
+    pub available: bool,
+}
+
+impl Capabilities {
+    /// Collect the capabilities of this build; external runtimes are probed
+    pub fn detect() -> Self {
+        let runtimes = RuntimeType::available_runtimes()
+            .into_iter()
+            .filter_map(RuntimeType::parse)
+            .map(|runtime| RuntimeInfo {
+                name: runtime.name().to_string(),
+                in_process: runtime.is_in_process(),
+                available: runtime.is_available(),
+            })
+            .collect();
+
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            input_types: vec!["player".into(), "preprocessed".into()],
+            challenge_types: challenge_types(),
+            runtimes,
+            error_kinds: vec![
+                JsChallengeErrorKind::Parse,
+                JsChallengeErrorKind::UnsupportedPlayer,
+                JsChallengeErrorKind::NoSolver,
+                JsChallengeErrorKind::JsException,
+                JsChallengeErrorKind::Timeout,
+                JsChallengeErrorKind::RuntimeUnavailable,
+                JsChallengeErrorKind::InvalidInput,
+                JsChallengeErrorKind::Implausible,
+                JsChallengeErrorKind::Internal,
+            ],
+            features: COMPILED_FEATURES.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+const COMPILED_FEATURES: &[&str] = &[
+    #[cfg(feature = "qjs")]
+    "qjs",
+    #[cfg(feature = "boa")]
+    "boa",
+    #[cfg(feature = "external")]
+    "external",
+    #[cfg(feature = "tokio")]
+    "tokio",
+    #[cfg(feature = "tracing")]
+    "tracing",
+    #[cfg(feature = "schema")]
+    "schema",
+];
+
+// ── jsc() JSON extraction ───────────────────────────────────────────────────
+//
+// yt-dlp's script format is:
+//   {lib.js} + Object.assign(globalThis, lib); + {core.js}
+//   + console.log(JSON.stringify(jsc({...json input...})));
+//
+// `ejs --script` extracts the JSON input from the jsc() call, avoiding the
+// need to run the meriyah-based JS solver inside QuickJS.
+
+/// Extracts the `JsChallengeInput` from a yt-dlp jsc() call script.
+/// Returns `None` if the extraction fails (e.g. unexpected format).
+///
+/// The script ends with:
+///   console.log(JSON.stringify(jsc({"type":"player","player":"...",...})));
+///
+/// We extract the JSON object between the last `jsc(` and its matching `)`.
+/// Braces inside JSON string values (e.g. the player source code) are safely
+/// skipped by tracking `"` delimiters and escape sequences.
+pub fn extract_jsc_input(code: &str) -> Option<JsChallengeInput> {
+    // Use the full marker for robustness — the player code could theoretically
+    // contain the literal text "jsc(" which would confuse a naive rfind.
+    let marker = "console.log(JSON.stringify(jsc(";
+    let jsc_pos = code.rfind(marker)?;
+    let start = jsc_pos + marker.len(); // position after "jsc("
+
+    // Find the JSON object by tracking braces
+    let bytes = code.as_bytes();
+    let mut pos = start;
+    let mut brace_depth: i32 = 0;
+    let mut json_start = start;
+
+    // Find the opening '{' of the JSON object
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'{' => {
+                brace_depth = 1;
+                json_start = pos;
+                pos += 1;
+                break;
+            }
+            _ => {
+                pos += 1;
+            }
+        }
+    }
+
+    // Track braces to find the matching closing '}'.
+    // JSON string values (like the player source) are skipped so braces
+    // inside them don't affect the count.
+    while pos < bytes.len() && brace_depth > 0 {
+        match bytes[pos] {
+            b'{' => brace_depth += 1,
+            b'}' => brace_depth -= 1,
+            b'"' => {
+                pos += 1;
+                while pos < bytes.len() && bytes[pos] != b'"' {
+                    if bytes[pos] == b'\\' {
+                        pos += 1; // skip escaped char (handles \\, \", \n, etc.)
+                    }
+                    pos += 1;
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    if brace_depth != 0 {
+        debug!(
+            "jsc JSON extraction failed: unmatched braces (depth={})",
+            brace_depth
+        );
+        return None;
+    }
+
+    let json_end = pos; // position after the closing '}'
+    // Both ends sit next to an ASCII `{`/`}`, but never index a `str` with
+    // positions from a byte scan
+    let json_str = code.get(json_start..json_end)?;
+
+    debug!(json_len = json_str.len(), "Extracted jsc JSON input");
+    serde_json::from_str::<JsChallengeInput>(json_str).ok()
+}
+
+// ── JSON Schema ──────────────────────────────────────────────────────────────
+
+/// JSON Schema of [`JsChallengeInput`](crate::JsChallengeInput)
+#[cfg(feature = "schema")]
+pub fn input_schema() -> schemars::Schema {
+    schemars::schema_for!(crate::provider::JsChallengeInput)
+}
+
+/// JSON Schema of [`JsChallengeOutput`](crate::JsChallengeOutput)
+#[cfg(feature = "schema")]
+pub fn output_schema() -> schemars::Schema {
+    schemars::schema_for!(crate::provider::JsChallengeOutput)
+}
+
+/// JSON Schema of [`Capabilities`]
+#[cfg(feature = "schema")]
+pub fn capabilities_schema() -> schemars::Schema {
+    schemars::schema_for!(Capabilities)
+}
@@ -2,43 +2,230 @@
 //!
 //! This module provides the core types for JavaScript challenge solving.
 
+use crate::plausibility::Implausibility;
+use crate::protocol::PROTOCOL_VERSION;
 use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
 use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Error type for JS Challenge operations
 #[derive(Debug, Error)]
 pub enum JsChallengeError {
-    #[error("Parse error: {0}")]
-    Parse(String),
-    #[error("Preprocess error: {0}")]
-    Preprocess(String),
+    #[error("Parse error: {message}")]
+    Parse {
+        message: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+    #[error("Unsupported player structure: {0}")]
+    UnsupportedPlayer(String),
+    #[error("Preprocess error: found 0 {0} functions")]
+    NoSolver(String),
+    #[error("JS exception: {0}")]
+    JsException(String),
+    #[error("Timed out after {} ms", .0.as_millis())]
+    Timeout(Duration),
+    #[error("Runtime unavailable: {runtime}: {message}")]
+    RuntimeUnavailable { runtime: String, message: String },
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Implausible {challenge_type} result: {reason}")]
+    Implausible {
+        challenge_type: String,
+        reason: Implausibility,
+    },
     #[error("Runtime error: {0}")]
     Runtime(String),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+impl JsChallengeError {
+    /// Parse error without a known source position
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::Parse {
+            message: message.into(),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// Stable category of this error, as reported in JSON output
+    pub fn kind(&self) -> JsChallengeErrorKind {
+        match self {
+            Self::Parse { .. } => JsChallengeErrorKind::Parse,
+            Self::UnsupportedPlayer(_) => JsChallengeErrorKind::UnsupportedPlayer,
+            Self::NoSolver(_) => JsChallengeErrorKind::NoSolver,
+            Self::JsException(_) => JsChallengeErrorKind::JsException,
+            Self::Timeout(_) => JsChallengeErrorKind::Timeout,
+            Self::RuntimeUnavailable { .. } => JsChallengeErrorKind::RuntimeUnavailable,
+            Self::InvalidInput(_) => JsChallengeErrorKind::InvalidInput,
+            Self::Implausible { .. } => JsChallengeErrorKind::Implausible,
+            Self::Runtime(_) | Self::Io(_) => JsChallengeErrorKind::Internal,
+        }
+    }
+
+    /// Structured details for the error kind, if any
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            Self::Parse {
+                line: Some(line),
+                column,
+                ..
+            } => Some(json!({ "line": line, "column": column })),
+            Self::NoSolver(challenge_type) => Some(json!({ "challenge_type": challenge_type })),
+            Self::Timeout(timeout) => Some(json!({ "timeout_ms": timeout.as_millis() as u64 })),
+            Self::RuntimeUnavailable { runtime, .. } => Some(json!({ "runtime": runtime })),
+            Self::Implausible {
+                challenge_type,
+                reason,
+            } => Some(json!({ "challenge_type": challenge_type, "reason": reason })),
+            Self::Io(e) => Some(json!({ "io_kind": e.kind().to_string() })),
+            _ => None,
+        }
+    }
+}
+
+/// Stable, machine-readable error category
+///
+/// Callers can decide between retrying, switching runtime or filing a bug
+/// without matching on error messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum JsChallengeErrorKind {
+    /// The player (or input script) is not valid JavaScript
+    Parse,
+    /// The player parsed, but its wrapper shape is not recognised
+    UnsupportedPlayer,
+    /// No solver function was found in the player
+    NoSolver,
+    /// The solver threw or the runtime reported a script error
+    JsException,
+    /// Solving took longer than the configured limit
+    Timeout,
+    /// The requested runtime is missing or could not be started
+    RuntimeUnavailable,
+    /// The request itself is malformed
+    InvalidInput,
+    /// The solver returned a value that looks like a silent failure
+    Implausible,
+    /// Any other failure, usually a bug in ejs
+    #[default]
+    Internal,
+}
+
+/// Failure of a single challenge within a request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct JsChallengeFailure {
+    pub kind: JsChallengeErrorKind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+impl From<&JsChallengeError> for JsChallengeFailure {
+    fn from(e: &JsChallengeError) -> Self {
+        Self {
+            kind: e.kind(),
+            message: e.to_string(),
+            details: e.details(),
+        }
+    }
+}
+
 /// Type of JavaScript challenge
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+///
+/// `n` and `sig` are always available. Any other name is routed to the
+/// extractor registered under it (see [`crate::register_extractor`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
 pub enum JsChallengeType {
     N,
     Sig,
+    Custom(String),
 }
 
 impl JsChallengeType {
-    /// Convert to static string slice
-    pub const fn as_str(&self) -> &'static str {
+    /// Name of the `_result` entry that solves this challenge type
+    pub fn as_str(&self) -> &str {
         match self {
             Self::N => "n",
             Self::Sig => "sig",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Check that challenges of this type can be routed to a solver
+    pub fn validate(&self) -> Result<(), JsChallengeError> {
+        match self {
+            Self::N | Self::Sig => Ok(()),
+            Self::Custom(name) if crate::registry::extractor(name).is_some() => Ok(()),
+            Self::Custom(name) => Err(JsChallengeError::InvalidInput(format!(
+                "Unknown challenge type: {}",
+                name
+            ))),
+        }
+    }
+}
+
+impl From<&str> for JsChallengeType {
+    fn from(s: &str) -> Self {
+        match s {
+            "n" => Self::N,
+            "sig" => Self::Sig,
+            name => Self::Custom(name.to_string()),
+        }
+    }
+}
+
+impl From<String> for JsChallengeType {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "n" => Self::N,
+            "sig" => Self::Sig,
+            _ => Self::Custom(s),
+        }
+    }
+}
+
+impl From<JsChallengeType> for String {
+    fn from(t: JsChallengeType) -> Self {
+        match t {
+            JsChallengeType::Custom(name) => name,
+            t => t.as_str().to_string(),
         }
     }
 }
 
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for JsChallengeType {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "JsChallengeType".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "`n`, `sig`, or the name of a registered extractor",
+            "examples": ["n", "sig"]
+        })
+    }
+}
+
+impl fmt::Display for JsChallengeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A request to solve a JavaScript challenge
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct JsChallengeRequest {
     #[serde(rename = "type")]
     pub challenge_type: JsChallengeType,
@@ -46,38 +233,126 @@ pub struct JsChallengeRequest {
 }
 
 /// Response from solving a JavaScript challenge
+///
+/// Challenges are solved independently: `data` holds the successful results
+/// and `errors` the failed ones, keyed by challenge. `Error` is only used
+/// when the request as a whole could not be attempted.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum JsChallengeResponse {
-    Result { data: HashMap<String, String> },
-    Error { error: String },
+    Result {
+        data: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        errors: HashMap<String, JsChallengeFailure>,
+        /// Results kept in `data` that failed a plausibility check
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        warnings: HashMap<String, JsChallengeFailure>,
+    },
+    Error {
+        error: String,
+        #[serde(default)]
+        kind: JsChallengeErrorKind,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
+    },
+}
+
+impl JsChallengeResponse {
+    /// Response for a request that could not be attempted at all
+    pub fn from_error(e: &JsChallengeError) -> Self {
+        Self::Error {
+            error: e.to_string(),
+            kind: e.kind(),
+            details: e.details(),
+        }
+    }
 }
 
 /// Input format for the challenge solver
+///
+/// `protocol_version` may be omitted by older clients and then means
+/// version 1.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum JsChallengeInput {
     Player {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        protocol_version: Option<u32>,
         player: String,
         requests: Vec<JsChallengeRequest>,
         output_preprocessed: bool,
     },
     Preprocessed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        protocol_version: Option<u32>,
         preprocessed_player: String,
         requests: Vec<JsChallengeRequest>,
     },
 }
 
+impl JsChallengeInput {
+    /// Protocol version the client speaks
+    pub fn protocol_version(&self) -> u32 {
+        let (Self::Player {
+            protocol_version, ..
+        }
+        | Self::Preprocessed {
+            protocol_version, ..
+        }) = self;
+        protocol_version.unwrap_or(1)
+    }
+}
+
 /// Output format from the challenge solver
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum JsChallengeOutput {
     Result {
+        #[serde(default = "legacy_protocol_version")]
+        protocol_version: u32,
         #[serde(skip_serializing_if = "Option::is_none")]
         preprocessed_player: Option<String>,
         responses: Vec<JsChallengeResponse>,
     },
     Error {
+        #[serde(default = "legacy_protocol_version")]
+        protocol_version: u32,
         error: String,
+        #[serde(default)]
+        kind: JsChallengeErrorKind,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        details: Option<Value>,
     },
 }
+
+impl JsChallengeOutput {
+    /// Successful output in the current protocol version
+    pub fn result(
+        preprocessed_player: Option<String>,
+        responses: Vec<JsChallengeResponse>,
+    ) -> Self {
+        Self::Result {
+            protocol_version: PROTOCOL_VERSION,
+            preprocessed_player,
+            responses,
+        }
+    }
+
+    /// Output for an input that could not be processed at all
+    pub fn from_error(e: &JsChallengeError) -> Self {
+        Self::Error {
+            protocol_version: PROTOCOL_VERSION,
+            error: e.to_string(),
+            kind: e.kind(),
+            details: e.details(),
+        }
+    }
+}
+
+/// Outputs written before versioning were version 1
+fn legacy_protocol_version() -> u32 {
+    1
+}
@@ -0,0 +1,138 @@
+//! Python Extension Module
+//!
+//! With the `python` feature, the crate builds a `ytdlp_ejs` Python module
+//! so a yt-dlp plugin can solve challenges in-process instead of spawning
+//! `ejs`. Inputs and outputs are plain dicts shaped like the JSON protocol
+//! and are converted directly to and from the [`crate::provider`] types.
+//!
+//! ```python
+//! import ytdlp_ejs
+//!
+//! output = ytdlp_ejs.solve({"type": "player", "player": code, "requests": [...],
+//!                           "output_preprocessed": False})
+//! solver = ytdlp_ejs.Solver(code, {"runtime": "qjs"})
+//! response = solver.solve({"type": "n", "challenges": ["ZdZIqFPQK-Ty8wId"]})
+//! ```
+//!
+//! Build with maturin (see `pyproject.toml`), which enables
+//! `python-extension`.
+
+use crate::builtin::preprocessor::preprocess_player_with;
+use crate::config::SolverConfig;
+use crate::director::process_input;
+use crate::protocol::PROTOCOL_VERSION;
+use crate::provider::{JsChallengeError, JsChallengeInput, JsChallengeRequest};
+use crate::solver::Solver;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pythonize::{depythonize, pythonize};
+
+create_exception!(ytdlp_ejs, EjsError, PyException);
+
+fn to_py_err(e: JsChallengeError) -> PyErr {
+    EjsError::new_err(e.to_string())
+}
+
+/// `None` means the default configuration
+fn config_from(config: Option<&Bound<'_, PyAny>>) -> PyResult<SolverConfig> {
+    match config {
+        Some(config) if !config.is_none() => Ok(depythonize(config)?),
+        _ => Ok(SolverConfig::default()),
+    }
+}
+
+/// Preprocess player code, returning the code the runtimes execute
+#[pyfunction]
+#[pyo3(name = "preprocess_player", signature = (player, config = None))]
+fn py_preprocess_player(
+    py: Python<'_>,
+    player: &str,
+    config: Option<&Bound<'_, PyAny>>,
+) -> PyResult<String> {
+    let config = config_from(config)?;
+    py.allow_threads(|| preprocess_player_with(player, &config))
+        .map_err(to_py_err)
+}
+
+/// Process a `JsChallengeInput` dict and return a `JsChallengeOutput` dict
+///
+/// Failures are reported inside the output, as in the JSON protocol.
+#[pyfunction]
+#[pyo3(signature = (input, config = None))]
+fn solve<'py>(
+    py: Python<'py>,
+    input: &Bound<'py, PyAny>,
+    config: Option<&Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let input: JsChallengeInput = depythonize(input)?;
+    let config = config_from(config)?;
+    let output = py.allow_threads(|| process_input(input, config));
+    Ok(pythonize(py, &output)?)
+}
+
+/// A preprocessed player with a live runtime, for repeated requests
+#[pyclass(name = "Solver", unsendable)]
+struct PySolver {
+    inner: Solver,
+}
+
+#[pymethods]
+impl PySolver {
+    /// Preprocess `player` and start the configured runtime
+    #[new]
+    #[pyo3(signature = (player, config = None))]
+    fn new(py: Python<'_>, player: &str, config: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let config = config_from(config)?;
+        let preprocessed = py
+            .allow_threads(|| crate::cache::preprocess_cached(player, &config))
+            .map_err(to_py_err)?;
+        Self::start(preprocessed, config)
+    }
+
+    /// Start the configured runtime with already preprocessed code
+    #[staticmethod]
+    #[pyo3(signature = (preprocessed, config = None))]
+    fn from_preprocessed(
+        preprocessed: String,
+        config: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        Self::start(preprocessed, config_from(config)?)
+    }
+
+    /// The preprocessed player code
+    #[getter]
+    fn preprocessed_player(&self) -> &str {
+        self.inner.preprocessed_player()
+    }
+
+    /// Solve a `JsChallengeRequest` dict and return a
+    /// `JsChallengeResponse` dict
+    fn solve<'py>(
+        &mut self,
+        py: Python<'py>,
+        request: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let request: JsChallengeRequest = depythonize(request)?;
+        Ok(pythonize(py, &self.inner.solve(&request))?)
+    }
+}
+
+impl PySolver {
+    fn start(preprocessed: String, config: SolverConfig) -> PyResult<Self> {
+        Solver::from_preprocessed(preprocessed, config)
+            .map(|inner| Self { inner })
+            .map_err(to_py_err)
+    }
+}
+
+#[pymodule]
+fn ytdlp_ejs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("PROTOCOL_VERSION", PROTOCOL_VERSION)?;
+    m.add("EjsError", m.py().get_type::<EjsError>())?;
+    m.add_function(wrap_pyfunction!(py_preprocess_player, m)?)?;
+    m.add_function(wrap_pyfunction!(solve, m)?)?;
+    m.add_class::<PySolver>()?;
+    Ok(())
+}
@@ -1,9 +1,16 @@
 //! JS Challenge Provider Registry
 //!
-//! This module manages runtime types and provider creation using enum dispatch.
+//! This module manages runtime types and provider creation using enum dispatch,
+//! and the process-wide set of solver extractors.
 
 use crate::builtin::JsRuntimeProvider;
+use crate::config::SolverConfig;
+use crate::extractor::{SolverExtractor, is_identifier};
 use crate::provider::JsChallengeError;
+use crate::runtime::{DefaultFactory, JsRuntime, JsRuntimeFactory};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
 
 /// Runtime type for JavaScript execution
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,29 +61,261 @@ impl RuntimeType {
         ]
     }
 
+    /// Canonical name, as accepted by [`RuntimeType::parse`]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "qjs")]
+            Self::QuickJS => "qjs",
+            #[cfg(feature = "boa")]
+            Self::Boa => "boa",
+            #[cfg(feature = "external")]
+            Self::Deno => "deno",
+            #[cfg(feature = "external")]
+            Self::Node => "node",
+            #[cfg(feature = "external")]
+            Self::Bun => "bun",
+        }
+    }
+
+    /// Runtime used when none is configured: embedded engines first
+    pub fn default_runtime() -> Option<Self> {
+        [
+            #[cfg(feature = "qjs")]
+            Self::QuickJS,
+            #[cfg(feature = "boa")]
+            Self::Boa,
+            #[cfg(feature = "external")]
+            Self::Node,
+            #[cfg(feature = "external")]
+            Self::Deno,
+            #[cfg(feature = "external")]
+            Self::Bun,
+        ]
+        .into_iter()
+        .next()
+    }
+
+    /// Whether the runtime is embedded rather than spawned as a process
+    pub const fn is_in_process(&self) -> bool {
+        match self {
+            #[cfg(feature = "qjs")]
+            Self::QuickJS => true,
+            #[cfg(feature = "boa")]
+            Self::Boa => true,
+            #[cfg(feature = "external")]
+            Self::Deno | Self::Node | Self::Bun => false,
+        }
+    }
+
+    /// Whether the runtime can be used; external runtimes are probed by
+    /// running `<program> --version`
+    pub fn is_available(&self) -> bool {
+        #[cfg(feature = "external")]
+        if matches!(self, Self::Deno | Self::Node | Self::Bun) {
+            return crate::builtin::probe(self.name());
+        }
+        true
+    }
+
     /// Create a provider instance for the specified runtime type
-    pub fn create_provider(&self, code: &str) -> Result<JsRuntimeProvider, JsChallengeError> {
+    pub fn create_provider(
+        &self,
+        code: &str,
+        config: &SolverConfig,
+    ) -> Result<JsRuntimeProvider, JsChallengeError> {
+        #[cfg(not(any(feature = "qjs", feature = "external")))]
+        let _ = config;
         match self {
             #[cfg(feature = "qjs")]
             RuntimeType::QuickJS => Ok(JsRuntimeProvider::QuickJS(
-                crate::builtin::quickjs::QuickJSJCP::new(code)?,
+                crate::builtin::quickjs::QuickJSJCP::with_config(code, config)?,
             )),
             #[cfg(feature = "boa")]
             RuntimeType::Boa => Ok(JsRuntimeProvider::Boa(Box::new(
                 crate::builtin::boa::BoaJCP::new(code)?,
             ))),
             #[cfg(feature = "external")]
-            RuntimeType::Deno => Ok(JsRuntimeProvider::Deno(crate::builtin::deno::DenoJCP::new(
-                code,
-            ))),
+            RuntimeType::Deno => Ok(JsRuntimeProvider::Deno(
+                crate::builtin::deno::DenoJCP::with_config(code, config),
+            )),
             #[cfg(feature = "external")]
-            RuntimeType::Node => Ok(JsRuntimeProvider::Node(crate::builtin::node::NodeJCP::new(
-                code,
-            ))),
+            RuntimeType::Node => Ok(JsRuntimeProvider::Node(
+                crate::builtin::node::NodeJCP::with_config(code, config),
+            )),
             #[cfg(feature = "external")]
-            RuntimeType::Bun => Ok(JsRuntimeProvider::Bun(crate::builtin::bun::BunJCP::new(
-                code,
+            RuntimeType::Bun => Ok(JsRuntimeProvider::Bun(
+                crate::builtin::bun::BunJCP::with_config(code, config),
+            )),
+        }
+    }
+}
+
+impl Serialize for RuntimeType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for RuntimeType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::parse(&name).ok_or_else(|| {
+            serde::de::Error::custom(format!(
+                "unknown runtime '{}'. Available: {}",
+                name,
+                Self::available_runtimes().join(", ")
+            ))
+        })
+    }
+}
+
+/// Runtime selection: a built-in engine or a custom implementation
+///
+/// Only built-in runtimes can be serialised, by name.
+#[derive(Clone)]
+pub enum Runtime {
+    Builtin(RuntimeType),
+    Custom(Arc<dyn JsRuntimeFactory>),
+}
+
+impl Runtime {
+    /// Use a custom [`JsRuntime`] created through [`JsRuntime::new`]
+    pub fn custom<R: JsRuntime + 'static>(name: &'static str) -> Self {
+        Self::Custom(Arc::new(DefaultFactory::<R> {
+            name,
+            runtime: std::marker::PhantomData,
+        }))
+    }
+
+    /// Name used in logs and errors
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Builtin(runtime_type) => runtime_type.name(),
+            Self::Custom(factory) => factory.name(),
+        }
+    }
+
+    /// Whether the runtime can be used; custom runtimes always report true
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Builtin(runtime_type) => runtime_type.is_available(),
+            Self::Custom(_) => true,
+        }
+    }
+
+    /// Create a provider instance for this runtime
+    pub fn create_provider(
+        &self,
+        code: &str,
+        config: &SolverConfig,
+    ) -> Result<JsRuntimeProvider, JsChallengeError> {
+        match self {
+            Self::Builtin(runtime_type) => runtime_type.create_provider(code, config),
+            Self::Custom(factory) => Ok(JsRuntimeProvider::Custom(factory.create(code)?)),
+        }
+    }
+}
+
+impl From<RuntimeType> for Runtime {
+    fn from(runtime_type: RuntimeType) -> Self {
+        Self::Builtin(runtime_type)
+    }
+}
+
+impl From<Arc<dyn JsRuntimeFactory>> for Runtime {
+    fn from(factory: Arc<dyn JsRuntimeFactory>) -> Self {
+        Self::Custom(factory)
+    }
+}
+
+impl From<Box<dyn JsRuntimeFactory>> for Runtime {
+    fn from(factory: Box<dyn JsRuntimeFactory>) -> Self {
+        Self::Custom(Arc::from(factory))
+    }
+}
+
+impl Serialize for Runtime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Builtin(runtime_type) => runtime_type.serialize(serializer),
+            Self::Custom(factory) => Err(serde::ser::Error::custom(format!(
+                "custom runtime '{}' cannot be serialised",
+                factory.name()
             ))),
         }
     }
 }
+
+impl<'de> Deserialize<'de> for Runtime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        RuntimeType::deserialize(deserializer).map(Self::Builtin)
+    }
+}
+
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Builtin(runtime_type) => fmt::Debug::fmt(runtime_type, f),
+            Self::Custom(factory) => f.debug_tuple("Custom").field(&factory.name()).finish(),
+        }
+    }
+}
+
+// ── Extractors ───────────────────────────────────────────────────────────────
+
+static EXTRACTORS: RwLock<Vec<Arc<dyn SolverExtractor>>> = RwLock::new(Vec::new());
+
+/// Register an extractor for an additional challenge type
+///
+/// Players preprocessed afterwards get a `_result.<name>` entry, and
+/// requests of type `<name>` are accepted.
+pub fn register_extractor(
+    extractor: impl SolverExtractor + 'static,
+) -> Result<(), JsChallengeError> {
+    let name = extractor.name();
+    if !is_identifier(name) {
+        return Err(JsChallengeError::InvalidInput(format!(
+            "Extractor name is not a JavaScript identifier: {:?}",
+            name
+        )));
+    }
+
+    let mut extractors = EXTRACTORS.write().unwrap_or_else(PoisonError::into_inner);
+    if matches!(name, "n" | "sig") || extractors.iter().any(|e| e.name() == name) {
+        return Err(JsChallengeError::InvalidInput(format!(
+            "Challenge type already registered: {}",
+            name
+        )));
+    }
+    extractors.push(Arc::new(extractor));
+    Ok(())
+}
+
+/// Names of all supported challenge types, built-in ones first
+pub fn challenge_types() -> Vec<String> {
+    let extractors = EXTRACTORS.read().unwrap_or_else(PoisonError::into_inner);
+    ["n", "sig"]
+        .into_iter()
+        .map(String::from)
+        .chain(extractors.iter().map(|e| e.name().to_string()))
+        .collect()
+}
+
+/// Whether `arg` is a `<type>:<challenge>` pair for a supported challenge
+/// type, as in `ejs player.js n:...`
+pub fn is_challenge_arg(arg: &str) -> bool {
+    arg.split_once(':')
+        .is_some_and(|(challenge_type, _)| challenge_types().iter().any(|t| t == challenge_type))
+}
+
+pub(crate) fn extractor(name: &str) -> Option<Arc<dyn SolverExtractor>> {
+    let extractors = EXTRACTORS.read().unwrap_or_else(PoisonError::into_inner);
+    extractors.iter().find(|e| e.name() == name).cloned()
+}
+
+pub(crate) fn extractors() -> Vec<Arc<dyn SolverExtractor>> {
+    EXTRACTORS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
@@ -0,0 +1,64 @@
+//! Pluggable JavaScript Runtimes
+//!
+//! Built-in engines are selected with [`RuntimeType`](crate::RuntimeType) and
+//! dispatched statically. Downstream crates can plug in their own engine by
+//! implementing [`JsRuntime`] and passing it as
+//! [`Runtime::custom`](crate::registry::Runtime::custom), or by implementing
+//! [`JsRuntimeFactory`] when the engine needs its own configuration.
+
+use crate::provider::JsChallengeError;
+
+/// Optional features a runtime may advertise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeCapabilities {
+    /// Runs inside the current process instead of spawning a child process
+    pub in_process: bool,
+    /// Keeps the evaluated player between calls, so setup is paid only once
+    pub persistent: bool,
+}
+
+/// A JavaScript engine able to run preprocessed player code
+///
+/// The preprocessed code assigns its solvers to a global `_result` object,
+/// which the runtime must provide before evaluating it.
+pub trait JsRuntime {
+    /// Create the runtime and evaluate the preprocessed player code
+    fn new(code: &str) -> Result<Self, JsChallengeError>
+    where
+        Self: Sized;
+
+    /// Call `_result[func_name](challenge)` and return its result as a string
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError>;
+
+    /// Optional capabilities of this runtime
+    fn capabilities(&self) -> RuntimeCapabilities {
+        RuntimeCapabilities::default()
+    }
+}
+
+/// Creates custom runtimes from preprocessed player code
+pub trait JsRuntimeFactory: Send + Sync {
+    /// Name used in logs and diagnostics
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Create a runtime that has evaluated `code`
+    fn create(&self, code: &str) -> Result<Box<dyn JsRuntime>, JsChallengeError>;
+}
+
+/// Factory that builds `R` through [`JsRuntime::new`]
+pub(crate) struct DefaultFactory<R> {
+    pub(crate) name: &'static str,
+    pub(crate) runtime: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R: JsRuntime + 'static> JsRuntimeFactory for DefaultFactory<R> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn create(&self, code: &str) -> Result<Box<dyn JsRuntime>, JsChallengeError> {
+        Ok(Box::new(R::new(code)?))
+    }
+}
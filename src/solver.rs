@@ -0,0 +1,448 @@
+//! Reusable Challenge Solver
+//!
+//! A [`Solver`] preprocesses a player once and keeps its runtime alive, so
+//! repeated requests against the same player skip parsing and engine setup.
+//! With the `tokio` feature, [`AsyncSolver`] offers the same API without
+//! blocking the executor.
+
+use crate::builtin::JsRuntimeProvider;
+use crate::cache::preprocess_cached;
+use crate::config::{SolverConfig, Strictness};
+use crate::plausibility;
+use crate::provider::{
+    JsChallengeError, JsChallengeFailure, JsChallengeRequest, JsChallengeResponse, JsChallengeType,
+};
+use crate::registry::Runtime;
+use crate::runtime::RuntimeCapabilities;
+use crate::trace::{debug, error, info, trace_span, warn};
+use std::collections::HashMap;
+
+/// Solver bound to a single preprocessed player and runtime
+pub struct Solver {
+    preprocessed: String,
+    provider: JsRuntimeProvider,
+    strictness: Strictness,
+}
+
+impl Solver {
+    /// Preprocess `player` and start the configured runtime with the result
+    ///
+    /// `config` is a [`SolverConfig`], or just the runtime to use.
+    pub fn new(player: &str, config: impl Into<SolverConfig>) -> Result<Self, JsChallengeError> {
+        let config = config.into();
+        info!(player_len = player.len(), "Preprocessing player code");
+        let preprocessed = preprocess_cached(player, &config)?;
+        debug!(
+            preprocessed_len = preprocessed.len(),
+            "Preprocessing complete"
+        );
+        Self::from_preprocessed(preprocessed, config)
+    }
+
+    /// Start the configured runtime with already preprocessed player code
+    pub fn from_preprocessed(
+        preprocessed: String,
+        config: impl Into<SolverConfig>,
+    ) -> Result<Self, JsChallengeError> {
+        let config = config.into();
+        let provider = with_fallbacks(&config, |runtime| {
+            debug!(?runtime, "Creating JS runtime provider");
+            let provider = runtime.create_provider(&preprocessed, &config)?;
+            info!(?runtime, "Runtime provider ready");
+            Ok(provider)
+        })?;
+        Ok(Self {
+            preprocessed,
+            provider,
+            strictness: config.strictness,
+        })
+    }
+
+    /// The preprocessed player code this solver runs
+    pub fn preprocessed_player(&self) -> &str {
+        &self.preprocessed
+    }
+
+    /// Capabilities of the underlying runtime
+    pub fn capabilities(&self) -> RuntimeCapabilities {
+        self.provider.capabilities()
+    }
+
+    /// Solve every challenge in `request`
+    pub fn solve(&mut self, request: &JsChallengeRequest) -> JsChallengeResponse {
+        solve_request(&mut self.provider, request, self.strictness)
+    }
+}
+
+/// Start the first runtime of `config` that comes up. Runtimes with a
+/// fallback behind them are probed first, so a missing external binary is
+/// skipped rather than failing every challenge later.
+fn with_fallbacks<T>(
+    config: &SolverConfig,
+    mut start: impl FnMut(Runtime) -> Result<T, JsChallengeError>,
+) -> Result<T, JsChallengeError> {
+    let runtimes = config.runtimes()?;
+    let count = runtimes.len();
+    let mut last_error = None;
+    for (i, runtime) in runtimes.into_iter().enumerate() {
+        let is_last = i + 1 == count;
+        let result = if is_last || runtime.is_available() {
+            start(runtime)
+        } else {
+            Err(unavailable(&runtime))
+        };
+        match result {
+            Ok(started) => return Ok(started),
+            Err(e) => {
+                if !is_last {
+                    error!(%e, "Runtime failed to start, trying next fallback");
+                }
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or_else(|| JsChallengeError::Runtime("No runtime configured".into())))
+}
+
+fn unavailable(runtime: &Runtime) -> JsChallengeError {
+    JsChallengeError::RuntimeUnavailable {
+        runtime: runtime.name().to_string(),
+        message: "not found or not runnable".into(),
+    }
+}
+
+fn solve_request(
+    provider: &mut JsRuntimeProvider,
+    request: &JsChallengeRequest,
+    strictness: Strictness,
+) -> JsChallengeResponse {
+    trace_span!(
+        "process_request",
+        req_type = %request.challenge_type.as_str(),
+        count = request.challenges.len()
+    );
+
+    if let Err(e) = request.challenge_type.validate() {
+        return error_response(e);
+    }
+
+    debug!(?request.challenges, "Solving challenges");
+    into_response(
+        &request.challenge_type,
+        provider.solve_challenges(&request.challenge_type, &request.challenges),
+        strictness,
+    )
+}
+
+/// Split per-challenge outcomes into the success, failure and warning maps,
+/// checking each solved value according to `strictness`
+fn into_response(
+    challenge_type: &JsChallengeType,
+    results: HashMap<String, Result<String, JsChallengeError>>,
+    strictness: Strictness,
+) -> JsChallengeResponse {
+    let mut data = HashMap::with_capacity(results.len());
+    let mut errors = HashMap::new();
+    let mut warnings = HashMap::new();
+    for (challenge, result) in results {
+        let result = result.and_then(|value| {
+            if strictness == Strictness::Off {
+                return Ok(value);
+            }
+            let Err(reason) = plausibility::check(challenge_type, &challenge, &value) else {
+                return Ok(value);
+            };
+            let e = JsChallengeError::Implausible {
+                challenge_type: challenge_type.to_string(),
+                reason,
+            };
+            if strictness == Strictness::Strict {
+                return Err(e);
+            }
+            warn!(%e, %challenge, %value, "Suspicious challenge result");
+            warnings.insert(challenge.clone(), JsChallengeFailure::from(&e));
+            Ok(value)
+        });
+        match result {
+            Ok(value) => {
+                data.insert(challenge, value);
+            }
+            Err(e) => {
+                error!(%e, %challenge, "Challenge solving failed");
+                errors.insert(challenge, JsChallengeFailure::from(&e));
+            }
+        }
+    }
+    info!(
+        results = data.len(),
+        failures = errors.len(),
+        warnings = warnings.len(),
+        ?data,
+        "Challenges solved"
+    );
+    JsChallengeResponse::Result {
+        data,
+        errors,
+        warnings,
+    }
+}
+
+/// Response for a request that could not be attempted at all
+fn error_response(e: JsChallengeError) -> JsChallengeResponse {
+    error!(%e, "Challenge request failed");
+    JsChallengeResponse::from_error(&e)
+}
+
+// ── Async solver (tokio) ─────────────────────────────────────────────────────
+//
+// External runtimes are driven through `tokio::process` with non-blocking
+// pipes. In-process engines (QuickJS, Boa) are not `Send`, so each one lives
+// on its own thread for the lifetime of the solver and receives requests
+// over a channel.
+//
+// Preprocessing and engines run on dedicated threads with
+// `SolverConfig::thread_stack_size`, not on tokio's blocking pool: large
+// players overflow its 2 MB default stack, and an engine would hold a pool
+// thread for as long as its solver lives.
+
+#[cfg(feature = "tokio")]
+type EngineJob = (
+    JsChallengeRequest,
+    tokio::sync::oneshot::Sender<JsChallengeResponse>,
+);
+
+/// Non-blocking solver bound to a single preprocessed player and runtime
+#[cfg(feature = "tokio")]
+pub struct AsyncSolver {
+    preprocessed: String,
+    backend: AsyncBackend,
+    strictness: Strictness,
+}
+
+#[cfg(feature = "tokio")]
+enum AsyncBackend {
+    #[cfg(feature = "external")]
+    Deno(crate::builtin::deno::DenoJCP),
+    #[cfg(feature = "external")]
+    Node(crate::builtin::node::NodeJCP),
+    #[cfg(feature = "external")]
+    Bun(crate::builtin::bun::BunJCP),
+    Engine(std::sync::mpsc::Sender<EngineJob>),
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncSolver {
+    /// Preprocess `player` on a dedicated thread and start the configured
+    /// runtime with the result
+    pub async fn new(
+        player: String,
+        config: impl Into<SolverConfig>,
+    ) -> Result<Self, JsChallengeError> {
+        let config = config.into();
+        info!(player_len = player.len(), "Preprocessing player code");
+        let preprocess_config = config.clone();
+        let stack_size = config.thread_stack_size;
+        let preprocessed = blocking(stack_size, move || {
+            preprocess_cached(&player, &preprocess_config)
+        })
+        .await??;
+        debug!(
+            preprocessed_len = preprocessed.len(),
+            "Preprocessing complete"
+        );
+        Self::from_preprocessed(preprocessed, config).await
+    }
+
+    /// Start the configured runtime with already preprocessed player code
+    pub async fn from_preprocessed(
+        preprocessed: String,
+        config: impl Into<SolverConfig>,
+    ) -> Result<Self, JsChallengeError> {
+        let config = config.into();
+        let runtimes = config.runtimes()?;
+        let count = runtimes.len();
+        let mut last_error = None;
+        for (i, runtime) in runtimes.into_iter().enumerate() {
+            let is_last = i + 1 == count;
+            let available = is_last || {
+                let probe = runtime.clone();
+                blocking(config.thread_stack_size, move || probe.is_available()).await?
+            };
+            let result = if available {
+                Self::start_backend(&preprocessed, runtime, &config).await
+            } else {
+                Err(unavailable(&runtime))
+            };
+            match result {
+                Ok(backend) => {
+                    info!("Async runtime provider ready");
+                    return Ok(Self {
+                        preprocessed,
+                        backend,
+                        strictness: config.strictness,
+                    });
+                }
+                Err(e) => {
+                    if !is_last {
+                        error!(%e, "Runtime failed to start, trying next fallback");
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| JsChallengeError::Runtime("No runtime configured".into())))
+    }
+
+    async fn start_backend(
+        preprocessed: &str,
+        runtime: Runtime,
+        config: &SolverConfig,
+    ) -> Result<AsyncBackend, JsChallengeError> {
+        debug!(?runtime, "Creating async JS runtime provider");
+        Ok(match runtime {
+            #[cfg(feature = "external")]
+            Runtime::Builtin(crate::RuntimeType::Deno) => AsyncBackend::Deno(
+                crate::builtin::deno::DenoJCP::with_config(preprocessed, config),
+            ),
+            #[cfg(feature = "external")]
+            Runtime::Builtin(crate::RuntimeType::Node) => AsyncBackend::Node(
+                crate::builtin::node::NodeJCP::with_config(preprocessed, config),
+            ),
+            #[cfg(feature = "external")]
+            Runtime::Builtin(crate::RuntimeType::Bun) => AsyncBackend::Bun(
+                crate::builtin::bun::BunJCP::with_config(preprocessed, config),
+            ),
+            runtime => AsyncBackend::Engine(
+                spawn_engine(preprocessed.to_string(), runtime, config.clone()).await?,
+            ),
+        })
+    }
+
+    /// The preprocessed player code this solver runs
+    pub fn preprocessed_player(&self) -> &str {
+        &self.preprocessed
+    }
+
+    /// Solve every challenge in `request`
+    pub async fn solve(&self, request: &JsChallengeRequest) -> JsChallengeResponse {
+        if let Err(e) = request.challenge_type.validate() {
+            return error_response(e);
+        }
+        match &self.backend {
+            #[cfg(feature = "external")]
+            AsyncBackend::Deno(p) => into_response(
+                &request.challenge_type,
+                solve_external(request, |name, challenge| p.solve_async(name, challenge)).await,
+                self.strictness,
+            ),
+            #[cfg(feature = "external")]
+            AsyncBackend::Node(p) => into_response(
+                &request.challenge_type,
+                solve_external(request, |name, challenge| p.solve_async(name, challenge)).await,
+                self.strictness,
+            ),
+            #[cfg(feature = "external")]
+            AsyncBackend::Bun(p) => into_response(
+                &request.challenge_type,
+                solve_external(request, |name, challenge| p.solve_async(name, challenge)).await,
+                self.strictness,
+            ),
+            AsyncBackend::Engine(jobs) => {
+                let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+                let stopped = || {
+                    error_response(JsChallengeError::Runtime(
+                        "Engine thread has stopped".into(),
+                    ))
+                };
+                if jobs.send((request.clone(), reply_tx)).is_err() {
+                    return stopped();
+                }
+                reply_rx.await.unwrap_or_else(|_| stopped())
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "tokio", feature = "external"))]
+async fn solve_external<'a, F, Fut>(
+    request: &'a JsChallengeRequest,
+    solve: F,
+) -> HashMap<String, Result<String, JsChallengeError>>
+where
+    F: Fn(&'a str, &'a str) -> Fut,
+    Fut: std::future::Future<Output = Result<String, JsChallengeError>>,
+{
+    let func_name = request.challenge_type.as_str();
+    let mut results = HashMap::with_capacity(request.challenges.len());
+    for challenge in &request.challenges {
+        results.insert(
+            challenge.clone(),
+            solve(func_name, challenge.as_str()).await,
+        );
+    }
+    results
+}
+
+/// Run `f` on a new thread with a `stack_size` byte stack
+#[cfg(feature = "tokio")]
+async fn blocking<T, F>(stack_size: usize, f: F) -> Result<T, JsChallengeError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    spawn_thread("ejs-blocking", stack_size, move || {
+        let _ = tx.send(f());
+    })?;
+    rx.await
+        .map_err(|_| JsChallengeError::Runtime("Blocking thread panicked".into()))
+}
+
+#[cfg(feature = "tokio")]
+fn spawn_thread(
+    name: &str,
+    stack_size: usize,
+    f: impl FnOnce() + Send + 'static,
+) -> Result<(), JsChallengeError> {
+    std::thread::Builder::new()
+        .name(name.into())
+        .stack_size(stack_size)
+        .spawn(f)
+        .map(drop)
+        .map_err(|e| JsChallengeError::Runtime(format!("Failed to spawn {} thread: {}", name, e)))
+}
+
+/// Start an in-process engine on its own thread and return its job queue.
+/// The thread exits once every sender (i.e. the owning `AsyncSolver`) is
+/// dropped.
+#[cfg(feature = "tokio")]
+async fn spawn_engine(
+    code: String,
+    runtime: Runtime,
+    config: SolverConfig,
+) -> Result<std::sync::mpsc::Sender<EngineJob>, JsChallengeError> {
+    let (job_tx, job_rx) = std::sync::mpsc::channel::<EngineJob>();
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+
+    spawn_thread("ejs-engine", config.thread_stack_size, move || {
+        let mut provider = match runtime.create_provider(&code, &config) {
+            Ok(provider) => {
+                let _ = ready_tx.send(Ok(()));
+                provider
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        while let Ok((request, reply)) = job_rx.recv() {
+            let _ = reply.send(solve_request(&mut provider, &request, config.strictness));
+        }
+        debug!(?runtime, "Engine thread finished");
+    })?;
+
+    ready_rx
+        .await
+        .map_err(|_| JsChallengeError::Runtime("Engine thread exited during setup".into()))??;
+    Ok(job_tx)
+}
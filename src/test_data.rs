@@ -1,5 +1,8 @@
 /// Test data for YouTube player solvers
+use crate::provider::JsChallengeError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub struct TestStep {
@@ -264,3 +267,172 @@ pub const TEST_CASES: &[TestCase] = &[
         }],
     },
 ];
+
+// ── Corpus manifest ─────────────────────────────────────────────────────────
+
+/// Environment variable naming a corpus manifest to use instead of
+/// [`MANIFEST_PATH`]
+pub const CORPUS_ENV: &str = "EJS_CORPUS";
+
+/// The manifest kept in the source tree, relative to the crate root and
+/// read from the working directory at run time
+pub const MANIFEST_PATH: &str = "tests/corpus.json";
+
+/// Test corpus, as stored in a JSON manifest such as `tests/corpus.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Corpus {
+    pub players: Vec<CorpusPlayer>,
+}
+
+/// One player release and its test vectors
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorpusPlayer {
+    /// Player id, e.g. `3d3ba064`
+    pub player: String,
+    /// Variants to test; all of [`ALL_VARIANTS`] when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<String>>,
+    /// Variants known to fail, skipped by every consumer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub known_failing: Vec<String>,
+    #[serde(default)]
+    pub n: Vec<CorpusStep>,
+    #[serde(default)]
+    pub sig: Vec<CorpusStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorpusStep {
+    pub input: String,
+    pub expected: String,
+}
+
+impl CorpusPlayer {
+    /// Variants to test, without the known-failing ones
+    pub fn variants(&self) -> Vec<&str> {
+        let variants: Vec<&str> = match &self.variants {
+            Some(variants) => variants.iter().map(String::as_str).collect(),
+            None => ALL_VARIANTS.to_vec(),
+        };
+        variants
+            .into_iter()
+            .filter(|v| !self.known_failing.iter().any(|f| f == v))
+            .collect()
+    }
+
+    /// `(type, steps)` for each challenge type
+    pub fn steps(&self) -> [(&'static str, &[CorpusStep]); 2] {
+        [("n", &self.n), ("sig", &self.sig)]
+    }
+}
+
+impl Corpus {
+    /// The embedded [`TEST_CASES`]
+    pub fn embedded() -> Self {
+        let steps = |steps: &[TestStep]| {
+            steps
+                .iter()
+                .map(|step| CorpusStep {
+                    input: step.input.to_string(),
+                    expected: step.expected.to_string(),
+                })
+                .collect()
+        };
+        let players = TEST_CASES
+            .iter()
+            .map(|case| CorpusPlayer {
+                player: case.player.to_string(),
+                variants: None,
+                // A restricted variant list marks the others as failing
+                known_failing: case.variants.map_or_else(Vec::new, |variants| {
+                    ALL_VARIANTS
+                        .iter()
+                        .filter(|v| !variants.contains(v))
+                        .map(|v| v.to_string())
+                        .collect()
+                }),
+                n: steps(case.n),
+                sig: steps(case.sig),
+            })
+            .collect();
+        Self { players }
+    }
+
+    /// Read a JSON manifest
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, JsChallengeError> {
+        let path = path.as_ref();
+        let data = std::fs::read_to_string(path)?;
+        serde_json::from_str(&data).map_err(|e| {
+            JsChallengeError::InvalidInput(format!("Invalid corpus {}: {}", path.display(), e))
+        })
+    }
+
+    /// Write as a JSON manifest
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), JsChallengeError> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    /// The manifest named by [`CORPUS_ENV`], else [`MANIFEST_PATH`] under
+    /// the working directory, else the embedded corpus
+    ///
+    /// `cargo test` and `cargo run` start in the crate root, so they pick up
+    /// the manifest of the source tree.
+    pub fn load() -> Result<Self, JsChallengeError> {
+        if let Some(path) = std::env::var_os(CORPUS_ENV) {
+            return Self::from_file(path);
+        }
+        if Path::new(MANIFEST_PATH).exists() {
+            return Self::from_file(MANIFEST_PATH);
+        }
+        Ok(Self::embedded())
+    }
+
+    /// Record `variant` of `player` and append the steps whose input is not
+    /// recorded yet; returns how many n and sig steps were added
+    ///
+    /// A known-failing variant that is added again is no longer skipped.
+    pub fn add(
+        &mut self,
+        player: &str,
+        variant: &str,
+        n: Vec<CorpusStep>,
+        sig: Vec<CorpusStep>,
+    ) -> (usize, usize) {
+        let index = match self.players.iter().position(|p| p.player == player) {
+            Some(index) => index,
+            None => {
+                self.players.push(CorpusPlayer {
+                    player: player.to_string(),
+                    variants: Some(Vec::new()),
+                    known_failing: Vec::new(),
+                    n: Vec::new(),
+                    sig: Vec::new(),
+                });
+                self.players.len() - 1
+            }
+        };
+        let entry = &mut self.players[index];
+        if let Some(variants) = &mut entry.variants
+            && !variants.iter().any(|v| v == variant)
+        {
+            variants.push(variant.to_string());
+        }
+        entry.known_failing.retain(|v| v != variant);
+
+        let append = |steps: &mut Vec<CorpusStep>, new: Vec<CorpusStep>| {
+            let before = steps.len();
+            for step in new {
+                if !steps.iter().any(|s| s.input == step.input) {
+                    steps.push(step);
+                }
+            }
+            steps.len() - before
+        };
+        (append(&mut entry.n, n), append(&mut entry.sig, sig))
+    }
+}
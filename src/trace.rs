@@ -7,11 +7,12 @@
 //! # Usage
 //!
 //! ```ignore
-//! use crate::trace::{debug, error, info, trace_span};
+//! use crate::trace::{debug, error, info, trace_span, warn};
 //!
 //! trace_span!("my_op", input_len = data.len());
 //! info!(result = %value, "Operation complete");
 //! debug!(?some_var, "Detailed state");
+//! warn!(%value, "Suspicious but kept");
 //! error!(%err, "Something went wrong");
 //! ```
 //!
@@ -53,6 +54,22 @@ macro_rules! info {
     ($($tt:tt)*) => {};
 }
 
+// ── warn! ────────────────────────────────────────────────────────────────────
+
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! warn {
+    ($($tt:tt)*) => {
+        ::tracing::warn!($($tt)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! warn {
+    ($($tt:tt)*) => {};
+}
+
 // ── error! ───────────────────────────────────────────────────────────────────
 
 #[cfg(feature = "tracing")]
@@ -99,3 +116,4 @@ pub use crate::debug;
 pub use crate::error;
 pub use crate::info;
 pub use crate::trace_span;
+pub use crate::warn;
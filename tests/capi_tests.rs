@@ -0,0 +1,79 @@
+//! Tests for the C ABI
+//!
+//! Run with: cargo test --features capi --test capi_tests
+
+#![cfg(feature = "capi")]
+
+use std::ffi::{CStr, CString};
+use ytdlp_ejs::capi::*;
+
+fn last_error() -> String {
+    let message = ejs_last_error();
+    assert!(!message.is_null());
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn take_string(s: *mut std::ffi::c_char) -> serde_json::Value {
+    assert!(!s.is_null(), "unexpected NULL: {}", last_error());
+    let json = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+    unsafe { ejs_string_free(s) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_committed_header_is_current() {
+    let committed = std::fs::read_to_string("include/ejs.h").unwrap();
+    assert!(
+        committed.replace("\r\n", "\n") == HEADER,
+        "include/ejs.h is stale: run cargo run --example update_header --features capi"
+    );
+}
+
+#[test]
+fn test_version() {
+    let version = unsafe { CStr::from_ptr(ejs_version()) };
+    assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_invalid_json_sets_last_error() {
+    let input = CString::new("{ not json").unwrap();
+
+    let output = unsafe { ejs_process_input(input.as_ptr(), std::ptr::null()) };
+
+    assert!(output.is_null());
+    assert!(last_error().starts_with("Invalid input: input"));
+}
+
+#[test]
+fn test_unparseable_player_fails_solver_creation() {
+    let player = b"function (";
+
+    let solver = unsafe { ejs_solver_new(player.as_ptr(), player.len(), std::ptr::null()) };
+
+    assert!(solver.is_null());
+    assert!(!last_error().is_empty());
+    unsafe { ejs_solver_free(solver) };
+}
+
+#[cfg(feature = "qjs")]
+#[test]
+fn test_process_input() {
+    let input = CString::new(
+        serde_json::json!({
+            "type": "preprocessed",
+            "preprocessed_player": "_result.n = (n) => n.split('').reverse().join('');",
+            "requests": [{ "type": "n", "challenges": ["abcdef"] }],
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let config = CString::new(r#"{ "runtime": "qjs" }"#).unwrap();
+
+    let output = take_string(unsafe { ejs_process_input(input.as_ptr(), config.as_ptr()) });
+
+    assert_eq!(output["type"], "result");
+    assert_eq!(output["responses"][0]["data"]["abcdef"], "fedcba");
+}
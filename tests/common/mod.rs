@@ -0,0 +1,114 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test crate uses only part of this module.
+#![allow(dead_code)]
+
+pub mod synthetic;
+
+use ytdlp_ejs::{
+    JsChallengeInput, JsChallengeOutput, JsChallengeRequest, JsChallengeResponse, JsChallengeType,
+};
+
+/// Input solving `n` and `sig` challenges for `player`
+pub fn player_input(player: String, n: Vec<String>, sig: Vec<String>) -> JsChallengeInput {
+    JsChallengeInput::Player {
+        protocol_version: None,
+        player,
+        requests: vec![
+            JsChallengeRequest {
+                challenge_type: JsChallengeType::N,
+                challenges: n,
+            },
+            JsChallengeRequest {
+                challenge_type: JsChallengeType::Sig,
+                challenges: sig,
+            },
+        ],
+        output_preprocessed: false,
+    }
+}
+
+/// Request for `challenges` of one type
+pub fn request(
+    challenge_type: impl Into<JsChallengeType>,
+    challenges: &[&str],
+) -> JsChallengeRequest {
+    JsChallengeRequest {
+        challenge_type: challenge_type.into(),
+        challenges: challenges.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+/// Input for already preprocessed `code`, e.g. empty for stub runtimes
+pub fn preprocessed_requests(code: &str, requests: Vec<JsChallengeRequest>) -> JsChallengeInput {
+    JsChallengeInput::Preprocessed {
+        protocol_version: None,
+        preprocessed_player: code.to_string(),
+        requests,
+    }
+}
+
+/// Input with one request and no player code, for stub runtimes
+pub fn preprocessed_input(
+    challenge_type: JsChallengeType,
+    challenges: &[&str],
+) -> JsChallengeInput {
+    preprocessed_requests("", vec![request(challenge_type, challenges)])
+}
+
+/// Responses of an output that must be a result
+pub fn responses(output: JsChallengeOutput) -> Vec<JsChallengeResponse> {
+    let JsChallengeOutput::Result { responses, .. } = output else {
+        panic!("expected result, got {:?}", output);
+    };
+    responses
+}
+
+/// The only response of an output that must be a result
+pub fn single_response(output: JsChallengeOutput) -> JsChallengeResponse {
+    let mut responses = responses(output);
+    assert_eq!(responses.len(), 1);
+    responses.remove(0)
+}
+
+/// Check one response against `(input, expected)` steps: `Ok` for each
+/// passing step, `Err` with a `FAIL:` message for each failing one
+pub fn check_steps<'a>(
+    name: &str,
+    test_type: &str,
+    response: Option<&JsChallengeResponse>,
+    steps: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<Result<(), String>> {
+    steps
+        .into_iter()
+        .map(|(input, expected)| match response {
+            Some(JsChallengeResponse::Result {
+                data,
+                errors: failures,
+                ..
+            }) => match (data.get(input), failures.get(input)) {
+                (Some(result), _) if result == expected => Ok(()),
+                (Some(result), _) => Err(format!(
+                    "FAIL: {} {} {}\n  Expected: {}\n  Got: {}",
+                    name, test_type, input, expected, result
+                )),
+                (None, Some(failure)) => Err(format!(
+                    "FAIL: {} {} {} - Error: {}",
+                    name, test_type, input, failure.message
+                )),
+                (None, None) => Err(format!(
+                    "FAIL: {} {} {} - No result returned",
+                    name, test_type, input
+                )),
+            },
+            Some(JsChallengeResponse::Error { error, .. }) => Err(format!(
+                "FAIL: {} {} {} - Error: {}",
+                name, test_type, input, error
+            )),
+            None => Err(format!(
+                "FAIL: {} {} {} - No result returned",
+                name, test_type, input
+            )),
+        })
+        .collect()
+}
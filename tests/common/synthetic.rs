@@ -0,0 +1,263 @@
+//! Synthetic players: small generated players in each wrapper shape whose
+//! n/sig answers are known, so tests need no downloaded players
+
+use ytdlp_ejs::test_data::CorpusStep;
+
+/// n challenges solved by every synthetic player
+pub const SYNTHETIC_N: &[&str] = &["ZdZIqFPQK-Ty8wId", "4GMrWHyKI5cEvhDO", "0eRGgQWJGfT5rFHFj"];
+
+/// sig challenges solved by every synthetic player
+pub const SYNTHETIC_SIG: &[&str] = &[
+    "gN7a-hudCuAuPH6fByOk1_GNXN0yNMHShjZXS2VOgsEItAJz0tipeavEOmNdYN-wUtcEqD3bCXjc0iyKfAyZxCBGgIARwsSdQfJ2CJtt",
+    "AAJAJfQdSswRQIhAMG5SN7-cAFChdrE7tLA6grH0rTMICA1mmDc0HoXgW3CAiAQQ4=CspfaF_vt82XH5yewvqcuEkvzeTsbRuHssRMyJQ=I",
+];
+
+const SYNTHETIC_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Wrapper around the player code, after the shapes of real players
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntheticShape {
+    /// `var _yt_player = {}; (function (g) { var window = this; ... }).call(this, _yt_player);`
+    TwoItem,
+    /// `(function () { ... }).call(this);`
+    OneItem,
+    /// One item, with the solvers assigned to the undeclared global `g` as
+    /// in TV players
+    Tv,
+}
+
+impl SyntheticShape {
+    pub const ALL: &[Self] = &[Self::TwoItem, Self::OneItem, Self::Tv];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::TwoItem => "two_item",
+            Self::OneItem => "one_item",
+            Self::Tv => "tv",
+        }
+    }
+}
+
+/// Parameters of a synthetic player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticSpec {
+    pub shape: SyntheticShape,
+    /// Varies the n and sig transforms
+    pub seed: u32,
+    /// Working solver candidates, at least one; with more than one, a
+    /// decoy candidate that throws is added as well
+    pub candidates: usize,
+}
+
+/// A generated player and its known answers
+#[derive(Debug, Clone)]
+pub struct SyntheticPlayer {
+    pub spec: SyntheticSpec,
+    pub source: String,
+    pub n: Vec<CorpusStep>,
+    pub sig: Vec<CorpusStep>,
+}
+
+impl SyntheticSpec {
+    /// Every shape, with one and with several solver candidates
+    pub fn all() -> Vec<Self> {
+        let mut specs = Vec::new();
+        for shape in SyntheticShape::ALL {
+            for candidates in [1, 3] {
+                specs.push(Self {
+                    shape: *shape,
+                    seed: specs.len() as u32 + 1,
+                    candidates,
+                });
+            }
+        }
+        specs
+    }
+
+    /// e.g. `synthetic-tv-5-c1`
+    pub fn name(&self) -> String {
+        format!(
+            "synthetic-{}-{}-c{}",
+            self.shape.name(),
+            self.seed,
+            self.candidates
+        )
+    }
+
+    /// Candidates the preprocessor should find for each of `n` and `sig`
+    pub fn expected_candidates(&self) -> usize {
+        self.candidates + usize::from(self.candidates > 1)
+    }
+
+    fn n_shift(&self) -> usize {
+        1 + self.seed as usize % 63
+    }
+
+    fn n_rotate(&self) -> usize {
+        self.seed as usize % 7
+    }
+
+    fn sig_swap(&self) -> usize {
+        3 + self.seed as usize % 11
+    }
+
+    fn sig_drop(&self) -> usize {
+        1 + self.seed as usize % 3
+    }
+
+    /// Reference implementation of the generated n transform: reverse,
+    /// rotate left, then shift every base64url character
+    pub fn solve_n(&self, challenge: &str) -> String {
+        let mut chars: Vec<char> = challenge.chars().rev().collect();
+        let rotate = self.n_rotate() % chars.len().max(1);
+        chars.rotate_left(rotate);
+        let alphabet = SYNTHETIC_ALPHABET.as_bytes();
+        chars
+            .into_iter()
+            .map(|c| match SYNTHETIC_ALPHABET.find(c) {
+                Some(i) => char::from(alphabet[(i + self.n_shift()) % alphabet.len()]),
+                None => c,
+            })
+            .collect()
+    }
+
+    /// Reference implementation of the generated sig transform: reverse,
+    /// swap the first character with another, then drop a prefix
+    pub fn solve_sig(&self, challenge: &str) -> String {
+        let mut chars: Vec<char> = challenge.chars().rev().collect();
+        if !chars.is_empty() {
+            let swap = self.sig_swap() % chars.len();
+            chars.swap(0, swap);
+        }
+        chars.into_iter().skip(self.sig_drop()).collect()
+    }
+
+    /// Generate the player source and its answers for [`SYNTHETIC_N`] and
+    /// [`SYNTHETIC_SIG`]
+    pub fn generate(&self) -> SyntheticPlayer {
+        let body = SYNTHETIC_BODY
+            .replace("$ALPHABET", SYNTHETIC_ALPHABET)
+            .replace("$N_SHIFT", &self.n_shift().to_string())
+            .replace("$N_ROTATE", &self.n_rotate().to_string())
+            .replace("$SIG_SWAP", &self.sig_swap().to_string())
+            .replace("$SIG_DROP", &self.sig_drop().to_string())
+            + &self.solvers();
+        let source = match self.shape {
+            SyntheticShape::TwoItem => format!(
+                "var _yt_player = {{}};\n(function (g) {{\nvar window = this;\n{}}}).call(this, _yt_player);\n",
+                body
+            ),
+            SyntheticShape::OneItem | SyntheticShape::Tv => {
+                format!("(function () {{\n{}}}).call(this);\n", body)
+            }
+        };
+        let step = |input: &str, expected: String| CorpusStep {
+            input: input.to_string(),
+            expected,
+        };
+        SyntheticPlayer {
+            spec: *self,
+            source,
+            n: SYNTHETIC_N
+                .iter()
+                .map(|c| step(c, self.solve_n(c)))
+                .collect(),
+            sig: SYNTHETIC_SIG
+                .iter()
+                .map(|c| step(c, self.solve_sig(c)))
+                .collect(),
+        }
+    }
+
+    /// Solver candidates: `var` declarators, or assignments to `g` for TV
+    fn solvers(&self) -> String {
+        let names: Vec<String> = (0..self.candidates).map(|i| format!("Xs{}", i)).collect();
+        let mut code = String::new();
+        match self.shape {
+            SyntheticShape::TwoItem | SyntheticShape::OneItem => {
+                let decls: Vec<String> = names
+                    .iter()
+                    .map(|name| format!("{} = {}", name, SYNTHETIC_SOLVER))
+                    .collect();
+                code += &format!("var {};\n", decls.join(",\n  "));
+                if self.candidates > 1 {
+                    code += &format!("var Xd = {};\n", SYNTHETIC_DECOY);
+                }
+            }
+            SyntheticShape::Tv => {
+                for name in &names {
+                    code += &format!("g.{} = {};\n", name, SYNTHETIC_SOLVER);
+                }
+                if self.candidates > 1 {
+                    code += &format!("g.Xd = {};\n", SYNTHETIC_DECOY);
+                }
+            }
+        }
+        code
+    }
+}
+
+/// Statements shared by every synthetic player. `Qu` stands in for the
+/// player's URL class; its first method after `set`, `get` and `clone`
+/// deciphers the `n` and `s` parameters.
+const SYNTHETIC_BODY: &str = r#"var Gw;
+Gw = this || self;
+_yt_not_defined();
+var Ab = "$ALPHABET";
+function Qu(url) {
+  this.url = url;
+  this.params = {};
+}
+Qu.prototype.set = function (k, v) {
+  this.params[k] = v;
+};
+Qu.prototype.get = function (k) {
+  return this.params[k];
+};
+Qu.prototype.clone = function () {
+  var u = new Qu(this.url);
+  for (var k in this.params) u.params[k] = this.params[k];
+  return u;
+};
+Qu.prototype.Dt = function () {
+  var n = this.params.n;
+  if (n) this.params.n = Nt(n);
+  var s = this.params.s;
+  if (s) this.params.s = Gw.encodeURIComponent(St(Gw.decodeURIComponent(s)));
+};
+var Nt = function (a) {
+  a = a.split("").reverse();
+  var r = $N_ROTATE % (a.length || 1);
+  a = a.slice(r).concat(a.slice(0, r));
+  return a
+    .map(function (c) {
+      var i = Ab.indexOf(c);
+      return i < 0 ? c : Ab.charAt((i + $N_SHIFT) % Ab.length);
+    })
+    .join("");
+};
+var St = function (a) {
+  a = a.split("").reverse();
+  if (a.length) {
+    var i = $SIG_SWAP % a.length,
+      c = a[0];
+    a[0] = a[i];
+    a[i] = c;
+  }
+  return a.slice($SIG_DROP).join("");
+};
+"#;
+
+const SYNTHETIC_SOLVER: &str = r#"function (a, b, c) {
+  var u = new Qu(a);
+  c && u.set(b, c);
+  u.set("alr", "yes");
+  return u;
+}"#;
+
+const SYNTHETIC_DECOY: &str = r#"function (a, b, c) {
+  if (!Gw.Xd_enabled) throw Error("decoy solver");
+  var u = new Qu(a);
+  u.set("alr", "yes");
+  return u;
+}"#;
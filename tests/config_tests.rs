@@ -0,0 +1,174 @@
+//! Integration tests for `SolverConfig`
+//!
+//! Run with: cargo test --test config_tests
+
+mod common;
+
+use common::{preprocessed_input, preprocessed_requests, request, single_response};
+use std::time::Duration;
+use ytdlp_ejs::{
+    Deterministic, JsChallengeError, JsChallengeOutput, JsChallengeResponse, JsChallengeType,
+    JsRuntime, PolyfillProfile, Runtime, SolverConfig, process_input,
+};
+
+struct BrokenRuntime;
+
+impl JsRuntime for BrokenRuntime {
+    fn new(_code: &str) -> Result<Self, JsChallengeError> {
+        Err(JsChallengeError::RuntimeUnavailable {
+            runtime: "broken".into(),
+            message: "always fails".into(),
+        })
+    }
+
+    fn solve(&mut self, _func_name: &str, _challenge: &str) -> Result<String, JsChallengeError> {
+        unreachable!()
+    }
+}
+
+struct UpperRuntime;
+
+impl JsRuntime for UpperRuntime {
+    fn new(_code: &str) -> Result<Self, JsChallengeError> {
+        Ok(Self)
+    }
+
+    fn solve(&mut self, _func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        Ok(challenge.to_uppercase())
+    }
+}
+
+#[test]
+fn test_config_defaults_from_empty_json() {
+    let config: SolverConfig = serde_json::from_str("{}").unwrap();
+
+    assert!(config.runtime.is_none());
+    assert_eq!(config.stack_size, 16 * 1024 * 1024);
+    assert_eq!(config.polyfills, PolyfillProfile::Full);
+    assert!(config.timeout().is_none());
+}
+
+#[test]
+fn test_config_rejects_unknown_fields() {
+    assert!(serde_json::from_str::<SolverConfig>(r#"{ "timeout": 5 }"#).is_err());
+}
+
+#[cfg(all(feature = "qjs", feature = "external"))]
+#[test]
+fn test_config_json_roundtrip() {
+    let json = r#"{
+        "runtime": "quickjs",
+        "fallbacks": ["node"],
+        "timeout_ms": 5000,
+        "polyfills": "minimal",
+        "minify": true
+    }"#;
+    let config: SolverConfig = serde_json::from_str(json).unwrap();
+
+    assert_eq!(config.runtime.as_ref().unwrap().name(), "qjs");
+    assert_eq!(config.fallbacks[0].name(), "node");
+    assert_eq!(config.timeout(), Some(Duration::from_secs(5)));
+
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["runtime"], "qjs");
+    assert_eq!(value["fallbacks"][0], "node");
+    assert_eq!(value["polyfills"], "minimal");
+}
+
+#[test]
+fn test_custom_runtime_is_not_serialisable() {
+    let config = SolverConfig::new().with_runtime(Runtime::custom::<UpperRuntime>("upper"));
+    assert!(serde_json::to_string(&config).is_err());
+}
+
+#[test]
+fn test_fallback_runtime_is_used() {
+    let config = SolverConfig::new()
+        .with_runtime(Runtime::custom::<BrokenRuntime>("broken"))
+        .with_fallback(Runtime::custom::<UpperRuntime>("upper"));
+
+    let response = single_response(process_input(
+        preprocessed_input(JsChallengeType::N, &["abc"]),
+        config,
+    ));
+
+    let JsChallengeResponse::Result { data, .. } = response else {
+        panic!("expected result response, got {:?}", response);
+    };
+    assert_eq!(data["abc"], "ABC");
+}
+
+#[test]
+fn test_last_runtime_error_is_reported() {
+    let config = SolverConfig::new()
+        .with_runtime(Runtime::custom::<BrokenRuntime>("broken"))
+        .with_fallback(Runtime::custom::<BrokenRuntime>("broken-too"));
+
+    let output = process_input(preprocessed_input(JsChallengeType::N, &["abc"]), config);
+
+    assert!(matches!(
+        output,
+        JsChallengeOutput::Error {
+            kind: ytdlp_ejs::JsChallengeErrorKind::RuntimeUnavailable,
+            ..
+        }
+    ));
+}
+
+#[cfg(feature = "qjs")]
+#[test]
+fn test_qjs_timeout() {
+    let config = SolverConfig::new()
+        .with_runtime(ytdlp_ejs::RuntimeType::QuickJS)
+        .with_timeout(Duration::from_millis(200));
+    let code = "_result.n = (n) => { while (true) {} };";
+
+    let response = single_response(process_input(
+        preprocessed_requests(code, vec![request("n", &["abc"])]),
+        config,
+    ));
+
+    let JsChallengeResponse::Result { errors, .. } = response else {
+        panic!("expected result response, got {:?}", response);
+    };
+    assert_eq!(errors["abc"].kind, ytdlp_ejs::JsChallengeErrorKind::Timeout);
+}
+
+#[test]
+fn test_deterministic_json() {
+    let config: SolverConfig =
+        serde_json::from_str(r#"{ "deterministic": { "seed": 7 } }"#).unwrap();
+
+    assert_eq!(
+        config.deterministic,
+        Some(Deterministic {
+            seed: 7,
+            ..Deterministic::default()
+        })
+    );
+}
+
+#[cfg(feature = "qjs")]
+#[test]
+fn test_deterministic_setup_code() {
+    let code = format!(
+        "globalThis.__ejs_deterministic = {{ seed: 1, now: 1000 }};\n{}\n\
+         _result.n = (n) => [Date.now(), new Date().getTime(), performance.now(), Math.random()].join(':');",
+        ytdlp_ejs::builtin::polyfill::SETUP_CODE
+    );
+    let solve = || {
+        let config = SolverConfig::new().with_runtime(ytdlp_ejs::RuntimeType::QuickJS);
+        let response = single_response(process_input(
+            preprocessed_requests(&code, vec![request("n", &["abc"])]),
+            config,
+        ));
+        let JsChallengeResponse::Result { data, .. } = response else {
+            panic!("expected result response, got {:?}", response);
+        };
+        data["abc"].clone()
+    };
+
+    let first = solve();
+    assert!(first.starts_with("1000:1000:0:"), "got {}", first);
+    assert_eq!(first, solve());
+}
@@ -0,0 +1,115 @@
+//! Tests for the corpus manifest in `tests/corpus.json`
+
+use std::path::Path;
+use ytdlp_ejs::test_data::{
+    ALL_VARIANTS, Corpus, CorpusPlayer, CorpusStep, MANIFEST_PATH, TEST_CASES,
+};
+
+fn manifest_path() -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_PATH)
+}
+
+fn step(input: &str, expected: &str) -> CorpusStep {
+    CorpusStep {
+        input: input.into(),
+        expected: expected.into(),
+    }
+}
+
+/// `ejs corpus add` may append variants and vectors, but never drops any
+#[test]
+fn test_manifest_extends_embedded_corpus() {
+    let manifest = Corpus::from_file(manifest_path()).unwrap();
+    let embedded = Corpus::embedded();
+    for player in &embedded.players {
+        let recorded = manifest
+            .players
+            .iter()
+            .find(|p| p.player == player.player)
+            .unwrap_or_else(|| panic!("{} is missing from the manifest", player.player));
+        for variant in player.variants() {
+            assert!(recorded.variants().contains(&variant), "{}", player.player);
+        }
+        for (steps, recorded_steps) in [(&player.n, &recorded.n), (&player.sig, &recorded.sig)] {
+            for step in steps {
+                assert!(
+                    recorded_steps.contains(step),
+                    "{} {}",
+                    player.player,
+                    step.input
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_add_new_player() {
+    let mut corpus = Corpus { players: vec![] };
+    let added = corpus.add("abcd1234", "tv", vec![step("a", "b")], vec![]);
+    assert_eq!(added, (1, 0));
+    let player = &corpus.players[0];
+    assert_eq!(player.player, "abcd1234");
+    assert_eq!(player.variants(), ["tv"]);
+}
+
+#[test]
+fn test_add_to_existing_player() {
+    let mut corpus = Corpus { players: vec![] };
+    corpus.add("abcd1234", "tv", vec![step("a", "b")], vec![]);
+    let added = corpus.add(
+        "abcd1234",
+        "main",
+        vec![step("a", "b"), step("c", "d")],
+        vec![step("e", "f")],
+    );
+    assert_eq!(added, (1, 1));
+    assert_eq!(corpus.players.len(), 1);
+    assert_eq!(corpus.players[0].variants(), ["tv", "main"]);
+    assert_eq!(corpus.players[0].n.len(), 2);
+}
+
+#[test]
+fn test_add_revives_known_failing_variant() {
+    let mut corpus = Corpus::embedded();
+    let player = corpus
+        .players
+        .iter()
+        .find(|p| !p.known_failing.is_empty())
+        .unwrap()
+        .clone();
+    let variant = player.known_failing[0].clone();
+    corpus.add(&player.player, &variant, vec![], vec![]);
+    let updated = corpus
+        .players
+        .iter()
+        .find(|p| p.player == player.player)
+        .unwrap();
+    assert!(updated.variants().contains(&variant.as_str()));
+}
+
+#[test]
+fn test_embedded_corpus_keeps_variants() {
+    let corpus = Corpus::embedded();
+    assert_eq!(corpus.players.len(), TEST_CASES.len());
+    for (player, case) in corpus.players.iter().zip(TEST_CASES) {
+        let expected = case.variants.unwrap_or(ALL_VARIANTS);
+        assert_eq!(player.variants(), expected, "{}", player.player);
+    }
+}
+
+#[test]
+fn test_variants_skip_known_failing() {
+    let player: CorpusPlayer = serde_json::from_str(
+        r#"{"player": "x", "variants": ["main", "tce", "tv"], "known_failing": ["tce"]}"#,
+    )
+    .unwrap();
+    assert_eq!(player.variants(), ["main", "tv"]);
+    assert!(player.n.is_empty());
+}
+
+#[test]
+fn test_manifest_rejects_unknown_fields() {
+    let result = serde_json::from_str::<Corpus>(r#"{"players": [{"player": "x", "nn": []}]}"#);
+    assert!(result.is_err());
+}
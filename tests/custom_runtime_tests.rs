@@ -0,0 +1,110 @@
+//! Integration tests for plugging custom runtimes into the pipeline
+//!
+//! Run with: cargo test --test custom_runtime_tests
+
+mod common;
+
+use common::{preprocessed_input, responses, single_response};
+use ytdlp_ejs::{
+    JsChallengeError, JsChallengeErrorKind, JsChallengeInput, JsChallengeOutput,
+    JsChallengeResponse, JsChallengeType, JsRuntime, Runtime, process_input,
+};
+
+/// Runtime that "solves" n challenges by reversing them and throws on
+/// empty ones
+struct ReverseRuntime;
+
+impl JsRuntime for ReverseRuntime {
+    fn new(_code: &str) -> Result<Self, JsChallengeError> {
+        Ok(Self)
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        match func_name {
+            "n" if !challenge.is_empty() => Ok(challenge.chars().rev().collect()),
+            "n" => Err(JsChallengeError::JsException("empty challenge".into())),
+            _ => Err(JsChallengeError::Runtime(format!(
+                "{} is not supported",
+                func_name
+            ))),
+        }
+    }
+}
+
+fn reverse(input: JsChallengeInput) -> JsChallengeOutput {
+    process_input(input, Runtime::custom::<ReverseRuntime>("reverse"))
+}
+
+#[test]
+fn test_custom_runtime_solves() {
+    let response = single_response(reverse(preprocessed_input(
+        JsChallengeType::N,
+        &["ZdZIqFPQK-Ty8wId"],
+    )));
+
+    let JsChallengeResponse::Result { data, errors, .. } = response else {
+        panic!("expected result response, got {:?}", response);
+    };
+    assert_eq!(data["ZdZIqFPQK-Ty8wId"], "dIw8yT-KQPFqIZdZ");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_custom_runtime_partial_failure() {
+    let response = single_response(reverse(preprocessed_input(
+        JsChallengeType::N,
+        &["ZdZIqFPQK-Ty8wId", ""],
+    )));
+
+    let JsChallengeResponse::Result { data, errors, .. } = response else {
+        panic!("expected result response, got {:?}", response);
+    };
+    assert_eq!(data.len(), 1);
+    assert_eq!(data["ZdZIqFPQK-Ty8wId"], "dIw8yT-KQPFqIZdZ");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[""].kind, JsChallengeErrorKind::JsException);
+}
+
+#[test]
+fn test_custom_runtime_errors() {
+    let response = single_response(reverse(preprocessed_input(
+        JsChallengeType::Sig,
+        &["ZdZIqFPQK-Ty8wId"],
+    )));
+
+    let JsChallengeResponse::Result { data, errors, .. } = response else {
+        panic!("expected result response, got {:?}", response);
+    };
+    assert!(data.is_empty());
+    assert!(errors["ZdZIqFPQK-Ty8wId"].message.contains("sig"));
+}
+
+#[test]
+fn test_run_preprocessed_groups_challenges() {
+    let output = ytdlp_ejs::run_preprocessed(
+        String::new(),
+        Runtime::custom::<ReverseRuntime>("reverse"),
+        vec!["n:abcdef".into(), "n:ZdZIqFPQK-Ty8wId".into()],
+    )
+    .unwrap();
+
+    let responses = responses(output);
+    // `n` and `sig` are always present
+    assert_eq!(responses.len(), 2);
+    let JsChallengeResponse::Result { data, .. } = &responses[0] else {
+        panic!("expected result response, got {:?}", responses[0]);
+    };
+    assert_eq!(data["abcdef"], "fedcba");
+    assert_eq!(data["ZdZIqFPQK-Ty8wId"], "dIw8yT-KQPFqIZdZ");
+}
+
+#[test]
+fn test_run_preprocessed_rejects_malformed_challenge() {
+    let result = ytdlp_ejs::run_preprocessed(
+        String::new(),
+        Runtime::custom::<ReverseRuntime>("reverse"),
+        vec!["abcdef".into()],
+    );
+
+    assert!(matches!(result, Err(JsChallengeError::InvalidInput(_))));
+}
@@ -0,0 +1,140 @@
+//! Integration tests for challenge types beyond `n` and `sig`
+//!
+//! Run with: cargo test --test extractor_tests
+
+mod common;
+
+use common::synthetic::SyntheticSpec;
+use common::{preprocessed_requests, request, responses};
+use ytdlp_ejs::extractor::ast::{Expr, Stmt};
+use ytdlp_ejs::registry::is_challenge_arg;
+use ytdlp_ejs::{
+    JsChallengeError, JsChallengeErrorKind, JsChallengeRequest, JsChallengeResponse,
+    JsChallengeType, JsRuntime, Runtime, SolverConfig, SolverExtractor, challenge_types,
+    preprocess_player_report, process_input, register_extractor,
+};
+
+/// Runtime that answers every challenge with the function it was routed to
+struct EchoRuntime;
+
+impl JsRuntime for EchoRuntime {
+    fn new(_code: &str) -> Result<Self, JsChallengeError> {
+        Ok(Self)
+    }
+
+    fn solve(&mut self, func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        Ok(format!("{}:{}", func_name, challenge))
+    }
+}
+
+struct NamedExtractor(&'static str);
+
+impl SolverExtractor for NamedExtractor {
+    fn name(&self) -> &str {
+        self.0
+    }
+
+    fn extract(&self, _stmt: &Stmt) -> Result<Vec<Box<Expr>>, JsChallengeError> {
+        Ok(Vec::new())
+    }
+}
+
+struct FailingExtractor;
+
+impl SolverExtractor for FailingExtractor {
+    fn name(&self) -> &str {
+        "failing_type"
+    }
+
+    fn extract(&self, _stmt: &Stmt) -> Result<Vec<Box<Expr>>, JsChallengeError> {
+        Err(JsChallengeError::Runtime("extractor bug".into()))
+    }
+}
+
+fn solve(requests: Vec<JsChallengeRequest>) -> Vec<JsChallengeResponse> {
+    let input = preprocessed_requests("", requests);
+    responses(process_input(input, Runtime::custom::<EchoRuntime>("echo")))
+}
+
+#[test]
+fn test_unknown_type_rejected_per_request() {
+    let responses = solve(vec![
+        request("n", &["abc"]),
+        request("unknown_type", &["abc"]),
+    ]);
+
+    let JsChallengeResponse::Result { data, .. } = &responses[0] else {
+        panic!("expected result response, got {:?}", responses[0]);
+    };
+    assert_eq!(data["abc"], "n:abc");
+
+    let JsChallengeResponse::Error { kind, error, .. } = &responses[1] else {
+        panic!("expected error response, got {:?}", responses[1]);
+    };
+    assert_eq!(*kind, JsChallengeErrorKind::InvalidInput);
+    assert!(error.contains("unknown_type"));
+}
+
+#[test]
+fn test_registered_type_is_routed() {
+    register_extractor(NamedExtractor("routed_type")).unwrap();
+    assert!(challenge_types().iter().any(|t| t == "routed_type"));
+
+    let responses = solve(vec![request("routed_type", &["abc"])]);
+
+    let JsChallengeResponse::Result { data, errors, .. } = &responses[0] else {
+        panic!("expected result response, got {:?}", responses[0]);
+    };
+    assert_eq!(data["abc"], "routed_type:abc");
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn test_failing_extractor_keeps_n_and_sig() {
+    register_extractor(FailingExtractor).unwrap();
+    let player = SyntheticSpec::all()[0].generate();
+
+    let (code, report) = preprocess_player_report(&player.source, &SolverConfig::default())
+        .expect("a failing extractor must not fail preprocessing");
+    assert!(report.candidates["n"] > 0);
+    assert!(report.candidates["sig"] > 0);
+    assert_eq!(report.candidates["failing_type"], 0);
+    assert!(report.extractor_errors["failing_type"].contains("extractor bug"));
+    assert!(code.contains("failing_type"));
+}
+
+/// `ejs player.js implicit_type:abc` is a solve, not a script
+#[test]
+fn test_registered_type_is_a_challenge_arg() {
+    assert!(is_challenge_arg("n:abc"));
+    assert!(!is_challenge_arg("implicit_type:abc"));
+
+    register_extractor(NamedExtractor("implicit_type")).unwrap();
+    assert!(is_challenge_arg("implicit_type:abc"));
+    assert!(!is_challenge_arg("implicit_type"));
+    assert!(!is_challenge_arg("other_type:abc"));
+}
+
+#[test]
+fn test_register_extractor_rejects_invalid_names() {
+    for name in ["n", "sig", "", "1st", "has-dash", "a.b"] {
+        assert!(
+            register_extractor(NamedExtractor(name)).is_err(),
+            "{:?} should be rejected",
+            name
+        );
+    }
+
+    register_extractor(NamedExtractor("duplicate_type")).unwrap();
+    assert!(register_extractor(NamedExtractor("duplicate_type")).is_err());
+}
+
+#[test]
+fn test_challenge_type_from_str() {
+    assert_eq!(JsChallengeType::from("n"), JsChallengeType::N);
+    assert_eq!(JsChallengeType::from("sig"), JsChallengeType::Sig);
+    assert_eq!(
+        JsChallengeType::from("other"),
+        JsChallengeType::Custom("other".into())
+    );
+}
@@ -0,0 +1,112 @@
+//! Regression tests for inputs found by, or written for, the fuzz targets in
+//! `fuzz/`
+//!
+//! Add every crashing input as a case here once it is fixed.
+
+mod common;
+
+use common::synthetic::SyntheticSpec;
+use ytdlp_ejs::protocol::extract_jsc_input;
+use ytdlp_ejs::{JsChallengeInput, preprocess_player};
+
+const MARKER: &str = "console.log(JSON.stringify(jsc(";
+
+// ── extract_jsc_input ───────────────────────────────────────────────────────
+
+#[test]
+fn test_jsc_multibyte_characters() {
+    let cases = [
+        // Escape before a multi-byte character
+        "{\"player\":\"\\é}\"}",
+        "{\"player\":\"\\😀\"}",
+        // Multi-byte characters around the braces
+        "é{😀}é",
+        "{é",
+        "😀",
+        "{\"a\":\"é\\",
+    ];
+    for case in cases {
+        let script = format!("{}{}", MARKER, case);
+        let _ = extract_jsc_input(&script);
+    }
+}
+
+#[test]
+fn test_jsc_truncated_scripts() {
+    let cases = [
+        String::new(),
+        MARKER.to_string(),
+        format!("{}{{", MARKER),
+        format!("{}{{\"", MARKER),
+        format!("{}{{\"\\", MARKER),
+        format!("{}}}}}", MARKER),
+        format!("{}{{\"player\":\"}}\"", MARKER),
+    ];
+    for case in &cases {
+        assert!(extract_jsc_input(case).is_none(), "{:?}", case);
+    }
+}
+
+#[test]
+fn test_jsc_braces_inside_strings() {
+    let script = format!(
+        "{}{})));",
+        MARKER,
+        r#"{"type":"player","player":"var a = {\"}\": \"é{\"};","requests":[],"output_preprocessed":false}"#
+    );
+    let Some(JsChallengeInput::Player { player, .. }) = extract_jsc_input(&script) else {
+        panic!("no input extracted");
+    };
+    assert_eq!(player, r#"var a = {"}": "é{"};"#);
+}
+
+// ── preprocess_player ───────────────────────────────────────────────────────
+
+#[test]
+fn test_preprocess_rejects_malformed_players() {
+    let source = SyntheticSpec::all()[0].generate().source;
+    let cases = [
+        "",
+        "(",
+        "é",
+        "(function () {}).call(this);",
+        "var a; var b; var c;",
+        &source[..source.len() / 2],
+    ];
+    for case in cases {
+        assert!(preprocess_player(case).is_err(), "{:?}", case);
+    }
+}
+
+#[test]
+fn test_preprocess_truncated_at_every_statement() {
+    let source = SyntheticSpec::all()[0].generate().source;
+    for (i, _) in source.match_indices(";\n") {
+        let _ = preprocess_player(&source[..i + 1]);
+    }
+}
+
+// ── Solver templates ────────────────────────────────────────────────────────
+
+#[cfg(feature = "fuzzing")]
+#[test]
+fn test_templates_quote_unusual_names() {
+    use ytdlp_ejs::builtin::preprocessor::generate_solver_code;
+
+    let body = r#"function (a) { a.set("alr", "yes"); }"#;
+    let names = [
+        "a.b",
+        r#"a["\u2028"]"#,
+        r#"a["\x07"]"#,
+        r#"a["\"})"]"#,
+        r#"a["é😀"]"#,
+        "a[1e21]",
+        "a.#b",
+    ];
+    for name in names {
+        let code = format!("{} = {};", name, body);
+        let _ = generate_solver_code(&code);
+    }
+    let generated = generate_solver_code(&format!(r#"a["\"})"] = {};"#, body)).unwrap();
+    assert_eq!(generated.len(), 1);
+}
@@ -0,0 +1,151 @@
+//! Tests for output plausibility checks
+//!
+//! Run with: cargo test --test plausibility_tests
+
+mod common;
+
+use common::{preprocessed_input, single_response};
+use ytdlp_ejs::plausibility::{Implausibility, check};
+use ytdlp_ejs::{
+    JsChallengeError, JsChallengeErrorKind, JsChallengeResponse, JsChallengeType, JsRuntime,
+    Runtime, SolverConfig, Strictness, process_input,
+};
+
+/// Returns every challenge unchanged, like a broken n function
+struct EchoRuntime;
+
+impl JsRuntime for EchoRuntime {
+    fn new(_code: &str) -> Result<Self, JsChallengeError> {
+        Ok(Self)
+    }
+
+    fn solve(&mut self, _func_name: &str, challenge: &str) -> Result<String, JsChallengeError> {
+        Ok(challenge.to_string())
+    }
+}
+
+fn solve_echo(strictness: Strictness) -> JsChallengeResponse {
+    let input = preprocessed_input(JsChallengeType::N, &["ZdZIqFPQK-Ty8wId"]);
+    let config = SolverConfig::new()
+        .with_runtime(Runtime::custom::<EchoRuntime>("echo"))
+        .with_strictness(strictness);
+    single_response(process_input(input, config))
+}
+
+#[test]
+fn test_real_results_are_plausible() {
+    assert_eq!(
+        check(&JsChallengeType::N, "ZdZIqFPQK-Ty8wId", "qmtUsIz04xxiNW"),
+        Ok(())
+    );
+    assert_eq!(check(&JsChallengeType::Sig, "abcdefgh", "hgfedc"), Ok(()));
+}
+
+#[test]
+fn test_n_failure_signatures() {
+    let n = JsChallengeType::N;
+    let challenge = "ZdZIqFPQK-Ty8wId";
+
+    assert_eq!(
+        check(&n, challenge, "enhanced_except_AAAA_ZdZIqFPQK-Ty8wId"),
+        Err(Implausibility::FailureSignature)
+    );
+    assert_eq!(
+        check(&n, challenge, "undefined"),
+        Err(Implausibility::FailureSignature)
+    );
+    assert_eq!(
+        check(&n, challenge, challenge),
+        Err(Implausibility::Identity)
+    );
+    assert_eq!(
+        check(&n, challenge, "qmtUsIz0/xxiNW"),
+        Err(Implausibility::Charset)
+    );
+    assert_eq!(check(&n, challenge, "qmt"), Err(Implausibility::Length));
+    assert_eq!(check(&n, challenge, ""), Err(Implausibility::Empty));
+}
+
+#[test]
+fn test_n_results_starting_like_a_signature() {
+    let n = JsChallengeType::N;
+    let challenge = "ZdZIqFPQK-Ty8wId";
+
+    for value in ["null", "NaN"] {
+        assert_eq!(
+            check(&n, challenge, value),
+            Err(Implausibility::FailureSignature)
+        );
+    }
+    for result in ["nullXq3sIz04xxiNW", "NaNmtUsIz04xxiN", "undefinedUsIz04x"] {
+        assert_eq!(check(&n, challenge, result), Ok(()), "{}", result);
+    }
+}
+
+#[test]
+fn test_sig_must_rearrange_the_challenge() {
+    let sig = JsChallengeType::Sig;
+
+    assert_eq!(
+        check(&sig, "abcdef", "abcdef"),
+        Err(Implausibility::Identity)
+    );
+    assert_eq!(
+        check(&sig, "abcdef", "fedcbz"),
+        Err(Implausibility::NotDerived)
+    );
+    assert_eq!(
+        check(&sig, "abcdef", "aab"),
+        Err(Implausibility::NotDerived)
+    );
+}
+
+#[test]
+fn test_custom_types_are_not_checked() {
+    let custom = JsChallengeType::Custom("other".into());
+    assert_eq!(check(&custom, "abc", "abc"), Ok(()));
+}
+
+#[test]
+fn test_warn_keeps_result() {
+    let JsChallengeResponse::Result {
+        data,
+        errors,
+        warnings,
+    } = solve_echo(Strictness::Warn)
+    else {
+        panic!("expected result response");
+    };
+    assert_eq!(data["ZdZIqFPQK-Ty8wId"], "ZdZIqFPQK-Ty8wId");
+    assert!(errors.is_empty());
+    assert_eq!(
+        warnings["ZdZIqFPQK-Ty8wId"].kind,
+        JsChallengeErrorKind::Implausible
+    );
+}
+
+#[test]
+fn test_strict_turns_result_into_error() {
+    let JsChallengeResponse::Result {
+        data,
+        errors,
+        warnings,
+    } = solve_echo(Strictness::Strict)
+    else {
+        panic!("expected result response");
+    };
+    assert!(data.is_empty());
+    assert!(warnings.is_empty());
+    let failure = &errors["ZdZIqFPQK-Ty8wId"];
+    assert_eq!(failure.kind, JsChallengeErrorKind::Implausible);
+    assert_eq!(failure.details.as_ref().unwrap()["reason"], "identity");
+}
+
+#[test]
+fn test_off_skips_checks() {
+    let JsChallengeResponse::Result { data, warnings, .. } = solve_echo(Strictness::Off) else {
+        panic!("expected result response");
+    };
+    assert_eq!(data.len(), 1);
+    assert!(warnings.is_empty());
+}
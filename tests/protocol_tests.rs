@@ -0,0 +1,124 @@
+//! Wire-format tests for the JSON protocol types
+//!
+//! Run with: cargo test --test protocol_tests
+
+use serde_json::json;
+use ytdlp_ejs::{
+    Capabilities, JsChallengeError, JsChallengeErrorKind, JsChallengeInput, JsChallengeOutput,
+    JsChallengeRequest, JsChallengeResponse, JsChallengeType, PROTOCOL_VERSION, process_input,
+};
+
+#[test]
+fn test_output_error_kind_and_details() {
+    let output = JsChallengeOutput::from_error(&JsChallengeError::NoSolver("n".into()));
+
+    assert_eq!(
+        serde_json::to_value(&output).unwrap(),
+        json!({
+            "type": "error",
+            "protocol_version": PROTOCOL_VERSION,
+            "error": "Preprocess error: found 0 n functions",
+            "kind": "no_solver",
+            "details": { "challenge_type": "n" },
+        })
+    );
+}
+
+#[test]
+fn test_response_error_without_details() {
+    let response =
+        JsChallengeResponse::from_error(&JsChallengeError::InvalidInput("bad request".into()));
+
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        json!({
+            "type": "error",
+            "error": "Invalid input: bad request",
+            "kind": "invalid_input",
+        })
+    );
+}
+
+#[test]
+fn test_error_without_kind_deserializes_as_internal() {
+    let output: JsChallengeOutput =
+        serde_json::from_value(json!({ "type": "error", "error": "boom" })).unwrap();
+
+    let JsChallengeOutput::Error {
+        protocol_version,
+        kind,
+        details,
+        ..
+    } = output
+    else {
+        panic!("expected error output");
+    };
+    assert_eq!(protocol_version, 1);
+    assert_eq!(kind, JsChallengeErrorKind::Internal);
+    assert!(details.is_none());
+}
+
+#[test]
+fn test_challenge_type_is_a_plain_string() {
+    let request: JsChallengeRequest =
+        serde_json::from_value(json!({ "type": "future", "challenges": ["abc"] })).unwrap();
+    assert_eq!(
+        request.challenge_type,
+        JsChallengeType::Custom("future".into())
+    );
+
+    let request = JsChallengeRequest {
+        challenge_type: JsChallengeType::Sig,
+        challenges: vec![],
+    };
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({ "type": "sig", "challenges": [] })
+    );
+}
+
+#[test]
+fn test_input_without_version_is_v1() {
+    let input: JsChallengeInput = serde_json::from_value(json!({
+        "type": "preprocessed",
+        "preprocessed_player": "",
+        "requests": [],
+    }))
+    .unwrap();
+    assert_eq!(input.protocol_version(), 1);
+}
+
+#[test]
+fn test_future_protocol_version_is_rejected() {
+    let input: JsChallengeInput = serde_json::from_value(json!({
+        "type": "preprocessed",
+        "protocol_version": PROTOCOL_VERSION + 1,
+        "preprocessed_player": "",
+        "requests": [],
+    }))
+    .unwrap();
+
+    let output = process_input(input, ytdlp_ejs::SolverConfig::default());
+
+    let JsChallengeOutput::Error { kind, .. } = output else {
+        panic!("expected error output, got {:?}", output);
+    };
+    assert_eq!(kind, JsChallengeErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_capabilities() {
+    let capabilities = Capabilities::detect();
+
+    assert_eq!(capabilities.protocol_version, PROTOCOL_VERSION);
+    assert!(capabilities.challenge_types.contains(&"n".to_string()));
+    assert!(capabilities.challenge_types.contains(&"sig".to_string()));
+    assert!(
+        capabilities
+            .error_kinds
+            .contains(&JsChallengeErrorKind::Timeout)
+    );
+
+    let value = serde_json::to_value(&capabilities).unwrap();
+    assert_eq!(value["input_types"], json!(["player", "preprocessed"]));
+}
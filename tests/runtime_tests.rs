@@ -1,16 +1,18 @@
-//! Integration tests using cases.csv data with different JS runtimes
+//! Integration tests using the test corpus with different JS runtimes
+//!
+//! Players come from `tests/corpus.json`; set `EJS_CORPUS` to test another
+//! manifest.
 //!
 //! Run with: cargo test --test runtime_tests
 //! Run specific runtime: cargo test --test runtime_tests --features qjs
 //! Run all runtimes: cargo test --test runtime_tests --all-features
 
-use ytdlp_ejs::test_data::{ALL_VARIANTS, TEST_CASES, get_cache_path};
-use ytdlp_ejs::{
-    JsChallengeInput, JsChallengeOutput, JsChallengeRequest, JsChallengeResponse, JsChallengeType,
-    RuntimeType, process_input,
-};
+mod common;
+
 use std::fs;
 use std::path::Path;
+use ytdlp_ejs::test_data::{Corpus, get_cache_path};
+use ytdlp_ejs::{JsChallengeInput, JsChallengeOutput, RuntimeType, process_input};
 
 struct TestCase {
     player_file: String,
@@ -20,40 +22,33 @@ struct TestCase {
     expected: String,
 }
 
+/// Cases from the corpus manifest named by `EJS_CORPUS`, or
+/// `tests/corpus.json`
 fn load_test_cases() -> Vec<TestCase> {
+    let corpus = Corpus::load().expect("Failed to load corpus");
     let mut cases = Vec::new();
 
-    for test_case in TEST_CASES {
-        let variants = test_case.variants.unwrap_or(ALL_VARIANTS);
-
-        for variant in variants {
-            let cache_path = get_cache_path(test_case.player, variant);
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let cache_path = get_cache_path(&player.player, variant);
             let path = Path::new(&cache_path);
 
             if !path.exists() {
                 continue;
             }
 
-            let player_name = format!("{}-{}", test_case.player, variant);
-
-            for step in test_case.n {
-                cases.push(TestCase {
-                    player_file: cache_path.clone(),
-                    player_name: player_name.clone(),
-                    test_type: "n".to_string(),
-                    input: step.input.to_string(),
-                    expected: step.expected.to_string(),
-                });
-            }
-
-            for step in test_case.sig {
-                cases.push(TestCase {
-                    player_file: cache_path.clone(),
-                    player_name: player_name.clone(),
-                    test_type: "sig".to_string(),
-                    input: step.input.to_string(),
-                    expected: step.expected.to_string(),
-                });
+            let player_name = format!("{}-{}", player.player, variant);
+
+            for (test_type, steps) in player.steps() {
+                for step in steps {
+                    cases.push(TestCase {
+                        player_file: cache_path.clone(),
+                        player_name: player_name.clone(),
+                        test_type: test_type.to_string(),
+                        input: step.input.clone(),
+                        expected: step.expected.clone(),
+                    });
+                }
             }
         }
     }
@@ -62,6 +57,12 @@ fn load_test_cases() -> Vec<TestCase> {
 }
 
 fn run_tests_with_runtime(runtime: RuntimeType) -> (usize, usize, Vec<String>) {
+    run_tests_with(|input| process_input(input, runtime))
+}
+
+fn run_tests_with(
+    solve: impl Fn(JsChallengeInput) -> JsChallengeOutput,
+) -> (usize, usize, Vec<String>) {
     let cases = load_test_cases();
     let mut passed = 0;
     let mut failed = 0;
@@ -92,100 +93,38 @@ fn run_tests_with_runtime(runtime: RuntimeType) -> (usize, usize, Vec<String>) {
             }
         };
 
-        let n_challenges: Vec<String> = player_cases
-            .iter()
-            .filter(|c| c.test_type == "n")
-            .map(|c| c.input.clone())
-            .collect();
-
-        let sig_challenges: Vec<String> = player_cases
-            .iter()
-            .filter(|c| c.test_type == "sig")
-            .map(|c| c.input.clone())
-            .collect();
-
-        let input = JsChallengeInput::Player {
-            player: content,
-            requests: vec![
-                JsChallengeRequest {
-                    challenge_type: JsChallengeType::N,
-                    challenges: n_challenges,
-                },
-                JsChallengeRequest {
-                    challenge_type: JsChallengeType::Sig,
-                    challenges: sig_challenges,
-                },
-            ],
-            output_preprocessed: false,
+        let challenges = |test_type: &str| -> Vec<String> {
+            player_cases
+                .iter()
+                .filter(|c| c.test_type == test_type)
+                .map(|c| c.input.clone())
+                .collect()
         };
+        let input = common::player_input(content, challenges("n"), challenges("sig"));
 
-        let output = process_input(input, runtime);
-
-        match output {
+        match solve(input) {
             JsChallengeOutput::Result { responses, .. } => {
-                // Check n results
-                if let Some(JsChallengeResponse::Result { data }) = responses.first() {
-                    for case in player_cases.iter().filter(|c| c.test_type == "n") {
-                        if let Some(result) = data.get(&case.input) {
-                            if result == &case.expected {
-                                passed += 1;
-                            } else {
-                                failed += 1;
-                                errors.push(format!(
-                                    "FAIL: {} n {}\n  Expected: {}\n  Got: {}",
-                                    case.player_name, case.input, case.expected, result
-                                ));
+                for (index, test_type) in ["n", "sig"].into_iter().enumerate() {
+                    for case in player_cases.iter().filter(|c| c.test_type == test_type) {
+                        let step = [(case.input.as_str(), case.expected.as_str())];
+                        for outcome in common::check_steps(
+                            &case.player_name,
+                            test_type,
+                            responses.get(index),
+                            step,
+                        ) {
+                            match outcome {
+                                Ok(()) => passed += 1,
+                                Err(e) => {
+                                    failed += 1;
+                                    errors.push(e);
+                                }
                             }
-                        } else {
-                            failed += 1;
-                            errors.push(format!(
-                                "FAIL: {} n {} - No result returned",
-                                case.player_name, case.input
-                            ));
                         }
                     }
-                } else if let Some(JsChallengeResponse::Error { error }) = responses.first() {
-                    for case in player_cases.iter().filter(|c| c.test_type == "n") {
-                        failed += 1;
-                        errors.push(format!(
-                            "FAIL: {} n {} - Error: {}",
-                            case.player_name, case.input, error
-                        ));
-                    }
-                }
-
-                // Check sig results
-                if let Some(JsChallengeResponse::Result { data }) = responses.get(1) {
-                    for case in player_cases.iter().filter(|c| c.test_type == "sig") {
-                        if let Some(result) = data.get(&case.input) {
-                            if result == &case.expected {
-                                passed += 1;
-                            } else {
-                                failed += 1;
-                                errors.push(format!(
-                                    "FAIL: {} sig {}\n  Expected: {}\n  Got: {}",
-                                    case.player_name, case.input, case.expected, result
-                                ));
-                            }
-                        } else {
-                            failed += 1;
-                            errors.push(format!(
-                                "FAIL: {} sig {} - No result returned",
-                                case.player_name, case.input
-                            ));
-                        }
-                    }
-                } else if let Some(JsChallengeResponse::Error { error }) = responses.get(1) {
-                    for case in player_cases.iter().filter(|c| c.test_type == "sig") {
-                        failed += 1;
-                        errors.push(format!(
-                            "FAIL: {} sig {} - Error: {}",
-                            case.player_name, case.input, error
-                        ));
-                    }
                 }
             }
-            JsChallengeOutput::Error { error } => {
+            JsChallengeOutput::Error { error, .. } => {
                 for case in &player_cases {
                     failed += 1;
                     errors.push(format!(
@@ -222,6 +161,39 @@ fn test_qjs_runtime() {
     );
 }
 
+#[cfg(all(feature = "qjs", feature = "tokio"))]
+#[test]
+fn test_qjs_runtime_async() {
+    // Preprocessing and the engine get their own large-stack threads, so a
+    // default runtime is enough
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let (passed, failed, errors) = run_tests_with(|input| {
+        rt.block_on(ytdlp_ejs::process_input_async(input, RuntimeType::QuickJS))
+    });
+
+    if !errors.is_empty() {
+        eprintln!("\n=== QuickJS (async) Runtime Errors ===");
+        for error in &errors {
+            eprintln!("{}", error);
+        }
+    }
+
+    eprintln!(
+        "\nQuickJS (async) Results: {}/{} passed",
+        passed,
+        passed + failed
+    );
+
+    assert!(
+        failed == 0 || passed > 0,
+        "QuickJS (async): {} tests failed out of {}",
+        failed,
+        passed + failed
+    );
+}
+
 #[cfg(feature = "boa")]
 #[test]
 fn test_boa_runtime() {
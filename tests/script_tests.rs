@@ -0,0 +1,119 @@
+//! Script mode (`ejs --script`, qjs compatibility) via the embedded QuickJS
+//!
+//! Run with: cargo test --test script_tests --features qjs
+
+#![cfg(feature = "qjs")]
+
+use ytdlp_ejs::{JsChallengeError, ScriptOptions, run_script, run_script_with};
+
+#[test]
+fn test_console_log_output() {
+    let output = run_script("console.log('a'); console.log(1 + 1);").unwrap();
+    assert_eq!(output, "a\n2\n");
+}
+
+#[test]
+fn test_script_args() {
+    let options = ScriptOptions {
+        args: vec!["script.js".into(), "x".into(), "y".into()],
+        ..Default::default()
+    };
+    let output = run_script_with("console.log(scriptArgs.join(','));", &options).unwrap();
+    assert_eq!(output, "script.js,x,y\n");
+}
+
+#[test]
+fn test_module_mode() {
+    let options = ScriptOptions {
+        module: true,
+        ..Default::default()
+    };
+    let code = "const x = 2; export const y = x * 3; console.log(y);";
+    assert_eq!(run_script_with(code, &options).unwrap(), "6\n");
+}
+
+#[test]
+fn test_std_global() {
+    let code = "std.out.puts('raw'); std.puts('!');";
+    let without = run_script(code);
+    assert!(matches!(without, Err(JsChallengeError::JsException(_))));
+
+    let options = ScriptOptions {
+        std: true,
+        ..Default::default()
+    };
+    assert_eq!(run_script_with(code, &options).unwrap(), "raw!");
+}
+
+#[test]
+fn test_script_exception() {
+    let result = run_script("throw new Error('boom');");
+    assert!(matches!(result, Err(JsChallengeError::JsException(_))));
+}
+
+#[test]
+fn test_multi_argument_logging() {
+    let code = "console.log('a', 1, true, null, [1, 2], { k: 'v' }); print('p', 2);";
+    assert_eq!(
+        run_script(code).unwrap(),
+        "a 1 true null [1,2] {\"k\":\"v\"}\np 2\n"
+    );
+}
+
+#[test]
+fn test_info_and_debug_go_to_stdout() {
+    let output = run_script("console.info('i'); console.debug('d');").unwrap();
+    assert_eq!(output, "i\nd\n");
+}
+
+#[test]
+fn test_promise_jobs_run() {
+    let code = "Promise.resolve(1).then(v => console.log('then', v)); \
+                queueMicrotask(() => console.log('micro')); \
+                console.log('sync');";
+    assert_eq!(run_script(code).unwrap(), "sync\nthen 1\nmicro\n");
+}
+
+#[test]
+fn test_timers_run_in_order() {
+    let code = "setTimeout(() => console.log('late'), 20); \
+                setTimeout((a, b) => console.log('early', a, b), 0, 'x', 'y'); \
+                const cancelled = setTimeout(() => console.log('never'), 5); \
+                clearTimeout(cancelled); \
+                let ticks = 0; \
+                const id = setInterval(() => { if (++ticks === 3) { clearInterval(id); console.log('ticks', ticks); } }, 1);";
+    assert_eq!(run_script(code).unwrap(), "early x y\nticks 3\nlate\n");
+}
+
+#[test]
+fn test_timer_exception_fails_script() {
+    let result = run_script("setTimeout(() => { throw new Error('late boom'); }, 0);");
+    assert!(matches!(result, Err(JsChallengeError::JsException(_))));
+}
+
+#[test]
+fn test_unhandled_rejection_fails_script() {
+    let result = run_script(
+        "async function main() { throw new Error('async boom'); } \
+         main(); console.log('started');",
+    );
+    assert!(matches!(result, Err(JsChallengeError::JsException(_))));
+}
+
+#[test]
+fn test_handled_rejection_is_not_reported() {
+    let code = "const p = Promise.reject(new Error('late')); \
+                setTimeout(() => p.catch(e => console.log(e.message)), 0);";
+    assert_eq!(run_script(code).unwrap(), "late\n");
+}
+
+#[test]
+fn test_module_top_level_await() {
+    let options = ScriptOptions {
+        module: true,
+        ..Default::default()
+    };
+    let code = "const v = await new Promise(resolve => setTimeout(() => resolve(42), 1)); \
+                console.log(v);";
+    assert_eq!(run_script_with(code, &options).unwrap(), "42\n");
+}
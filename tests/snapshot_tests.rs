@@ -0,0 +1,164 @@
+//! Golden snapshots of the preprocessed output for every corpus player
+//!
+//! Each synthetic player and each downloaded player variant is preprocessed
+//! with the default config and compared against
+//! `tests/snapshots/<player>-<variant>.json` (`synthetic-<spec>.json` for
+//! synthetic players), which records a hash of the normalised output
+//! together with the solvers that were found. Synthetic players need no
+//! network and must always have a snapshot; corpus players that have not
+//! been downloaded, or have no committed snapshot, are skipped.
+//!
+//! Regenerate the snapshots after an intended change and commit the diff:
+//! EJS_UPDATE_SNAPSHOTS=1 cargo test --test snapshot_tests
+
+mod common;
+
+use common::synthetic::SyntheticSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use ytdlp_ejs::test_data::{Corpus, get_cache_path};
+use ytdlp_ejs::{SolverConfig, preprocess_player_report};
+
+const UPDATE_ENV: &str = "EJS_UPDATE_SNAPSHOTS";
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Snapshot {
+    player: String,
+    /// Length of the normalised output in bytes
+    output_len: usize,
+    /// FNV-1a hash of the normalised output, in hex
+    output_hash: String,
+    /// Solver functions found, in source order
+    solvers: Vec<String>,
+    /// Candidate count per `_result` property
+    candidates: BTreeMap<String, usize>,
+}
+
+fn snapshot_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn update_mode() -> bool {
+    std::env::var(UPDATE_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Line endings and trailing whitespace do not change what engines run
+fn normalise(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    for line in code.lines() {
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn fnv1a64(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in data {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+fn take_snapshot(name: &str, source: &str) -> Snapshot {
+    let (code, report) = preprocess_player_report(source, &SolverConfig::default())
+        .unwrap_or_else(|e| panic!("{}: preprocessing failed: {}", name, e));
+    let code = normalise(&code);
+    Snapshot {
+        player: name.to_string(),
+        output_len: code.len(),
+        output_hash: format!("{:016x}", fnv1a64(code.as_bytes())),
+        solvers: report.solvers,
+        candidates: report.candidates,
+    }
+}
+
+/// `(name, source, synthetic)` of every synthetic player and downloaded
+/// corpus player
+fn players() -> Vec<(String, String, bool)> {
+    let mut players: Vec<(String, String, bool)> = SyntheticSpec::all()
+        .iter()
+        .map(|spec| (spec.name(), spec.generate().source, true))
+        .collect();
+    let corpus = Corpus::load().expect("Failed to load corpus");
+    for player in &corpus.players {
+        for variant in player.variants() {
+            let cache_path = get_cache_path(&player.player, variant);
+            if let Ok(source) = fs::read_to_string(&cache_path) {
+                players.push((format!("{}-{}", player.player, variant), source, false));
+            }
+        }
+    }
+    players
+}
+
+#[test]
+fn test_preprocessed_snapshots() {
+    let update = update_mode();
+    let dir = snapshot_dir();
+    if update {
+        fs::create_dir_all(&dir).unwrap();
+    }
+
+    let mut checked = 0;
+    let mut problems = Vec::new();
+    for (name, source, synthetic) in players() {
+        let path = dir.join(format!("{}.json", name));
+        if !update && !synthetic && !path.exists() {
+            eprintln!("{}: no snapshot committed, skipping", name);
+            continue;
+        }
+        let actual = take_snapshot(&name, &source);
+        checked += 1;
+
+        if update {
+            let json = serde_json::to_string_pretty(&actual).unwrap();
+            fs::write(&path, json + "\n").unwrap();
+            continue;
+        }
+        let expected: Snapshot = match fs::read_to_string(&path) {
+            Ok(json) => {
+                serde_json::from_str(&json).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+            }
+            Err(_) => {
+                problems.push(format!("{}: no snapshot", name));
+                continue;
+            }
+        };
+        if expected != actual {
+            problems.push(format!(
+                "{}: snapshot differs\n  expected: {:?}\n  actual:   {:?}",
+                name, expected, actual
+            ));
+        }
+    }
+
+    if update {
+        println!("Updated {} snapshots in {}", checked, dir.display());
+        return;
+    }
+    assert!(
+        problems.is_empty(),
+        "{} of {} snapshots do not match (rerun with {}=1 to update):\n{}",
+        problems.len(),
+        checked,
+        UPDATE_ENV,
+        problems.join("\n")
+    );
+}
+
+#[test]
+fn test_normalise_ignores_line_endings() {
+    assert_eq!(
+        normalise("a = 1;  \r\nb = 2;\n"),
+        normalise("a = 1;\nb = 2;")
+    );
+    assert_ne!(
+        fnv1a64(normalise("a = 1;").as_bytes()),
+        fnv1a64(normalise("a = 2;").as_bytes())
+    );
+}
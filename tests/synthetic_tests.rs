@@ -0,0 +1,158 @@
+//! Hermetic tests using generated synthetic players
+//!
+//! Unlike `runtime_tests`, these need no downloaded players: every wrapper
+//! shape is generated with known n/sig answers and solved with each
+//! runtime that is built in or installed.
+//!
+//! Run with: cargo test --test synthetic_tests --all-features
+
+mod common;
+
+use common::synthetic::{SyntheticPlayer, SyntheticShape, SyntheticSpec};
+use ytdlp_ejs::plausibility;
+use ytdlp_ejs::test_data::CorpusStep;
+use ytdlp_ejs::{
+    JsChallengeOutput, JsChallengeType, RuntimeType, SolverConfig, preprocess_player_report,
+    process_input,
+};
+
+fn players() -> Vec<SyntheticPlayer> {
+    SyntheticSpec::all()
+        .iter()
+        .map(SyntheticSpec::generate)
+        .collect()
+}
+
+/// Failures from solving every synthetic player with `runtime`
+fn solve_all(runtime: RuntimeType) -> Vec<String> {
+    let mut errors = Vec::new();
+    for player in players() {
+        let name = player.spec.name();
+        let inputs = |steps: &[CorpusStep]| -> Vec<String> {
+            steps.iter().map(|s| s.input.clone()).collect()
+        };
+        let input = common::player_input(
+            player.source.clone(),
+            inputs(&player.n),
+            inputs(&player.sig),
+        );
+        let responses = match process_input(input, runtime) {
+            JsChallengeOutput::Result { responses, .. } => responses,
+            JsChallengeOutput::Error { error, .. } => {
+                errors.push(format!("FAIL: {} - Processing error: {}", name, error));
+                continue;
+            }
+        };
+        for (index, (test_type, steps)) in [("n", &player.n), ("sig", &player.sig)]
+            .into_iter()
+            .enumerate()
+        {
+            let steps = steps
+                .iter()
+                .map(|s| (s.input.as_str(), s.expected.as_str()));
+            errors.extend(
+                common::check_steps(&name, test_type, responses.get(index), steps)
+                    .into_iter()
+                    .filter_map(Result::err),
+            );
+        }
+    }
+    errors
+}
+
+fn check_runtime(runtime: RuntimeType) {
+    if !runtime.is_available() {
+        eprintln!("{} is not available, skipping", runtime.name());
+        return;
+    }
+    let errors = solve_all(runtime);
+    assert!(
+        errors.is_empty(),
+        "{}: {} failures\n{}",
+        runtime.name(),
+        errors.len(),
+        errors.join("\n")
+    );
+}
+
+#[test]
+fn test_every_shape_preprocesses() {
+    for player in players() {
+        let spec = player.spec;
+        let (_, report) = preprocess_player_report(&player.source, &SolverConfig::default())
+            .unwrap_or_else(|e| panic!("{}: {}", spec.name(), e));
+        assert_eq!(
+            report.solvers.len(),
+            spec.expected_candidates(),
+            "{}: {:?}",
+            spec.name(),
+            report.solvers
+        );
+        assert_eq!(report.candidates["n"], spec.expected_candidates());
+        assert_eq!(report.candidates["sig"], spec.expected_candidates());
+    }
+}
+
+#[test]
+fn test_tv_solvers_live_on_global_g() {
+    for player in players()
+        .iter()
+        .filter(|p| p.spec.shape == SyntheticShape::Tv)
+    {
+        let (_, report) =
+            preprocess_player_report(&player.source, &SolverConfig::default()).unwrap();
+        assert!(report.solvers.iter().all(|name| name.starts_with("g.")));
+    }
+}
+
+#[test]
+fn test_expected_answers_are_plausible() {
+    for player in players() {
+        for step in &player.n {
+            plausibility::check(&JsChallengeType::N, &step.input, &step.expected).unwrap();
+        }
+        for step in &player.sig {
+            plausibility::check(&JsChallengeType::Sig, &step.input, &step.expected).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_seeds_change_answers() {
+    let players = players();
+    assert!(
+        players
+            .windows(2)
+            .all(|pair| pair[0].n[0].expected != pair[1].n[0].expected)
+    );
+}
+
+#[cfg(feature = "qjs")]
+#[test]
+fn test_qjs_runtime() {
+    check_runtime(RuntimeType::QuickJS);
+}
+
+#[cfg(feature = "boa")]
+#[test]
+fn test_boa_runtime() {
+    check_runtime(RuntimeType::Boa);
+}
+
+#[cfg(feature = "external")]
+#[test]
+fn test_deno_runtime() {
+    check_runtime(RuntimeType::Deno);
+}
+
+#[cfg(feature = "external")]
+#[test]
+fn test_node_runtime() {
+    check_runtime(RuntimeType::Node);
+}
+
+#[cfg(feature = "external")]
+#[test]
+fn test_bun_runtime() {
+    check_runtime(RuntimeType::Bun);
+}